@@ -2,10 +2,12 @@ use criterion::criterion_main;
 
 mod compute_normals;
 mod render_layers;
+mod shader_cache;
 mod torus;
 
 criterion_main!(
     render_layers::benches,
     compute_normals::benches,
+    shader_cache::benches,
     torus::benches
 );
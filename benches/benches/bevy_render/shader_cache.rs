@@ -0,0 +1,84 @@
+use core::hint::black_box;
+use std::sync::Mutex;
+
+use criterion::{criterion_group, Criterion};
+use parking_lot::RwLock;
+
+use bevy_asset::{uuid::Uuid, AssetId};
+use bevy_render::render_resource::{DownlevelFlags, WgpuFeatures};
+use bevy_shader::{Shader, ShaderCache};
+use bevy_tasks::TaskPoolBuilder;
+
+const PIPELINES: usize = 50;
+
+const SHADER: &str = "@compute @workgroup_size(64) fn main() {}";
+
+fn shader_cache() -> (ShaderCache<(), ()>, AssetId<Shader>) {
+    let mut shader_cache = ShaderCache::new(
+        WgpuFeatures::empty(),
+        DownlevelFlags::empty(),
+        |_, _, _, _| Ok(()),
+    );
+    let id = AssetId::Uuid {
+        uuid: Uuid::from_u128(1),
+    };
+    shader_cache.set_shader(id, Shader::from_wgsl(SHADER, "shader.wgsl"));
+    // Process the shader up front, like the first pipeline using it would.
+    shader_cache.get(&(), 0, id, &[], None).unwrap();
+    (shader_cache, id)
+}
+
+/// Many pipelines using the same already processed shader, each fetching its module from its own
+/// task, like pipelines compiled in parallel by the `PipelineCache`.
+fn concurrent_pipelines(c: &mut Criterion) {
+    let mut group = c.benchmark_group("shader_cache_concurrent_pipelines");
+    let pool = TaskPoolBuilder::new().build();
+
+    group.bench_function("mutex", |b| {
+        let (shader_cache, id) = shader_cache();
+        let shader_cache = Mutex::new(shader_cache);
+        b.iter(|| {
+            pool.scope(|scope| {
+                for pipeline in 0..PIPELINES {
+                    let shader_cache = &shader_cache;
+                    scope.spawn(async move {
+                        black_box(
+                            shader_cache
+                                .lock()
+                                .unwrap()
+                                .get(&(), pipeline, id, &[], None)
+                                .unwrap(),
+                        );
+                    });
+                }
+            });
+        });
+    });
+
+    group.bench_function("rwlock", |b| {
+        let (shader_cache, id) = shader_cache();
+        let shader_cache = RwLock::new(shader_cache);
+        b.iter(|| {
+            pool.scope(|scope| {
+                for pipeline in 0..PIPELINES {
+                    let shader_cache = &shader_cache;
+                    scope.spawn(async move {
+                        let module = shader_cache.read().get_processed(pipeline, id, &[], None);
+                        let module = match module {
+                            Some(module) => module,
+                            None => shader_cache
+                                .write()
+                                .get(&(), pipeline, id, &[], None)
+                                .unwrap(),
+                        };
+                        black_box(module);
+                    });
+                }
+            });
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, concurrent_pipelines);
@@ -389,6 +389,7 @@ pub fn initialize_generated_environment_map_resources(
             defs
         },
         entry_point: Some("downsample_first".into()),
+        constants: Vec::new(),
         zero_initialize_workgroup_memory: false,
     });
 
@@ -405,6 +406,7 @@ pub fn initialize_generated_environment_map_resources(
             defs
         },
         entry_point: Some("downsample_second".into()),
+        constants: Vec::new(),
         zero_initialize_workgroup_memory: false,
     });
 
@@ -416,6 +418,7 @@ pub fn initialize_generated_environment_map_resources(
         shader: env_filter_shader.clone(),
         shader_defs: shader_defs.clone(),
         entry_point: Some("generate_radiance_map".into()),
+        constants: Vec::new(),
         zero_initialize_workgroup_memory: false,
     });
 
@@ -427,6 +430,7 @@ pub fn initialize_generated_environment_map_resources(
         shader: env_filter_shader,
         shader_defs: shader_defs.clone(),
         entry_point: Some("generate_irradiance_map".into()),
+        constants: Vec::new(),
         zero_initialize_workgroup_memory: false,
     });
 
@@ -438,6 +442,7 @@ pub fn initialize_generated_environment_map_resources(
         shader: copy_shader,
         shader_defs: vec![],
         entry_point: Some("copy".into()),
+        constants: Vec::new(),
         zero_initialize_workgroup_memory: false,
     });
 
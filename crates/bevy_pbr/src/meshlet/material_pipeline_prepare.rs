@@ -218,6 +218,8 @@ pub fn prepare_material_meshlet_meshes_main_opaque_pass(
                     entry_point: material_fragment.entry_point,
                     targets: material_fragment.targets,
                 }),
+                multiview: None,
+                constants: Vec::new(),
                 zero_initialize_workgroup_memory: false,
             };
             let type_id = material_id.type_id();
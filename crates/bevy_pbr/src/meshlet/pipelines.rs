@@ -290,6 +290,7 @@ pub fn init_meshlet_pipelines(
                 shader: downsample_depth_shader,
                 shader_defs: vec!["MESHLET".into()],
                 entry_point: Some("downsample_depth_second".into()),
+                constants: Vec::new(),
                 zero_initialize_workgroup_memory: false,
             },
         ),
@@ -4,6 +4,7 @@
 mod as_bind_group;
 mod extract_component;
 mod extract_resource;
+mod shader_defs;
 mod specializer;
 
 use bevy_macro_utils::{derive_label, BevyManifest};
@@ -19,6 +20,10 @@ pub(crate) fn bevy_ecs_path() -> syn::Path {
     BevyManifest::shared(|manifest| manifest.get_path("bevy_ecs"))
 }
 
+pub(crate) fn bevy_shader_path() -> syn::Path {
+    BevyManifest::shared(|manifest| manifest.get_path("bevy_shader"))
+}
+
 #[proc_macro_derive(ExtractResource)]
 pub fn derive_extract_resource(input: TokenStream) -> TokenStream {
     extract_resource::derive_extract_resource(input)
@@ -121,6 +126,31 @@ pub fn derive_specializer_key(input: TokenStream) -> TokenStream {
     specializer::impl_specializer_key(input)
 }
 
+/// Derive macro generating an impl of the trait `ShaderDefs`.
+///
+/// Each field becomes a shader def named after the field in uppercase, or after the
+/// `#[shader_def = "NAME"]` attribute. `bool` fields add a def if they are `true`, `u32` fields
+/// add a `ShaderDefVal::UInt` and `i32` fields a `ShaderDefVal::Int`. Other field types aren't
+/// supported.
+///
+/// # Example
+///
+/// ```no_compile
+/// use bevy_render::render_resource::ShaderDefs;
+///
+/// #[derive(ShaderDefs)]
+/// pub struct MyPipelineKey {
+///     // Adds `SKINNED` if `true`.
+///     pub skinned: bool,
+///     #[shader_def = "SAMPLE_COUNT"]
+///     pub msaa_samples: u32,
+/// }
+/// ```
+#[proc_macro_derive(ShaderDefs, attributes(shader_def))]
+pub fn derive_shader_defs(input: TokenStream) -> TokenStream {
+    shader_defs::derive_shader_defs(input)
+}
+
 #[proc_macro_derive(ShaderLabel)]
 pub fn derive_shader_label(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -0,0 +1,86 @@
+use bevy_macro_utils::{get_struct_fields, require_named};
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput, Expr, ExprLit, Lit, Meta, Path, Type};
+
+const SHADER_DEF_ATTRIBUTE_NAME: &str = "shader_def";
+
+pub fn derive_shader_defs(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    let bevy_render_path: Path = crate::bevy_render_path();
+    let bevy_shader_path: Path = crate::bevy_shader_path();
+
+    let fields = match get_struct_fields(&ast.data, "derive(ShaderDefs)").and_then(require_named) {
+        Ok(fields) => fields,
+        Err(err) => return err.into_compile_error().into(),
+    };
+
+    let mut pushes = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+
+        let mut name = ident.to_string().to_uppercase();
+        for attr in &field.attrs {
+            if !attr.path().is_ident(SHADER_DEF_ATTRIBUTE_NAME) {
+                continue;
+            }
+            match &attr.meta {
+                Meta::NameValue(meta) => match &meta.value {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(lit), ..
+                    }) => name = lit.value(),
+                    value => {
+                        return syn::Error::new_spanned(value, "expected a string literal")
+                            .into_compile_error()
+                            .into();
+                    }
+                },
+                meta => {
+                    return syn::Error::new_spanned(meta, "expected `#[shader_def = \"NAME\"]`")
+                        .into_compile_error()
+                        .into();
+                }
+            }
+        }
+
+        let type_name = match &field.ty {
+            Type::Path(path) => path.path.get_ident().map(ToString::to_string),
+            _ => None,
+        };
+        let push = match type_name.as_deref() {
+            Some("bool") => quote! {
+                if self.#ident {
+                    shader_defs.push(#bevy_shader_path::ShaderDefVal::Bool(#name.into(), true));
+                }
+            },
+            Some("u32") => quote! {
+                shader_defs.push(#bevy_shader_path::ShaderDefVal::UInt(#name.into(), self.#ident));
+            },
+            Some("i32") => quote! {
+                shader_defs.push(#bevy_shader_path::ShaderDefVal::Int(#name.into(), self.#ident));
+            },
+            _ => {
+                return syn::Error::new_spanned(
+                    &field.ty,
+                    "ShaderDefs can only be derived for `bool`, `u32` and `i32` fields",
+                )
+                .into_compile_error()
+                .into();
+            }
+        };
+        pushes.push(push);
+    }
+
+    let struct_name = &ast.ident;
+    let (impl_generics, type_generics, where_clause) = &ast.generics.split_for_impl();
+
+    TokenStream::from(quote! {
+        impl #impl_generics #bevy_render_path::render_resource::ShaderDefs for #struct_name #type_generics #where_clause {
+            fn shader_defs(&self) -> Vec<#bevy_shader_path::ShaderDefVal> {
+                let mut shader_defs = Vec::new();
+                #(#pushes)*
+                shader_defs
+            }
+        }
+    })
+}
@@ -5,6 +5,7 @@
 mod erased_render_asset_diagnostic_plugin;
 pub(crate) mod internal;
 mod mesh_allocator_diagnostic_plugin;
+mod pipeline_cache_diagnostics_plugin;
 mod render_asset_diagnostic_plugin;
 #[cfg(feature = "tracing-tracy")]
 mod tracy_gpu;
@@ -22,6 +23,7 @@ use self::internal::{
 pub use self::{
     erased_render_asset_diagnostic_plugin::ErasedRenderAssetDiagnosticPlugin,
     mesh_allocator_diagnostic_plugin::MeshAllocatorDiagnosticPlugin,
+    pipeline_cache_diagnostics_plugin::PipelineCacheDiagnosticsPlugin,
     render_asset_diagnostic_plugin::RenderAssetDiagnosticPlugin,
 };
 
@@ -0,0 +1,104 @@
+use bevy_app::{Plugin, PreUpdate};
+use bevy_diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic};
+use bevy_ecs::{resource::Resource, system::Res};
+use bevy_platform::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::{render_resource::PipelineCache, Extract, ExtractSchedule, RenderApp};
+
+/// Number of pipelines queued for creation
+static PIPELINE_CACHE_QUEUED: DiagnosticPath = DiagnosticPath::const_new("pipeline_cache/queued");
+
+/// Number of pipelines being created
+static PIPELINE_CACHE_CREATING: DiagnosticPath =
+    DiagnosticPath::const_new("pipeline_cache/creating");
+
+/// Number of pipelines created successfully
+static PIPELINE_CACHE_READY: DiagnosticPath = DiagnosticPath::const_new("pipeline_cache/ready");
+
+/// Number of pipelines that failed to be created
+static PIPELINE_CACHE_FAILED: DiagnosticPath = DiagnosticPath::const_new("pipeline_cache/failed");
+
+/// Records the number of pipelines in each state of the [`PipelineCache`] as diagnostics, from
+/// [`PipelineCache::stats()`].
+pub struct PipelineCacheDiagnosticsPlugin;
+
+impl PipelineCacheDiagnosticsPlugin {
+    /// Get the [`DiagnosticPath`] for queued pipelines
+    pub fn queued_diagnostic_path() -> &'static DiagnosticPath {
+        &PIPELINE_CACHE_QUEUED
+    }
+    /// Get the [`DiagnosticPath`] for pipelines being created
+    pub fn creating_diagnostic_path() -> &'static DiagnosticPath {
+        &PIPELINE_CACHE_CREATING
+    }
+    /// Get the [`DiagnosticPath`] for created pipelines
+    pub fn ready_diagnostic_path() -> &'static DiagnosticPath {
+        &PIPELINE_CACHE_READY
+    }
+    /// Get the [`DiagnosticPath`] for failed pipelines
+    pub fn failed_diagnostic_path() -> &'static DiagnosticPath {
+        &PIPELINE_CACHE_FAILED
+    }
+}
+
+impl Plugin for PipelineCacheDiagnosticsPlugin {
+    fn build(&self, app: &mut bevy_app::App) {
+        app.register_diagnostic(
+            Diagnostic::new(PIPELINE_CACHE_QUEUED.clone()).with_suffix(" pipelines"),
+        )
+        .register_diagnostic(
+            Diagnostic::new(PIPELINE_CACHE_CREATING.clone()).with_suffix(" pipelines"),
+        )
+        .register_diagnostic(
+            Diagnostic::new(PIPELINE_CACHE_READY.clone()).with_suffix(" pipelines"),
+        )
+        .register_diagnostic(
+            Diagnostic::new(PIPELINE_CACHE_FAILED.clone()).with_suffix(" pipelines"),
+        )
+        .init_resource::<PipelineCacheMeasurements>()
+        .add_systems(PreUpdate, add_pipeline_cache_measurement);
+
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.add_systems(ExtractSchedule, measure_pipeline_cache);
+        }
+    }
+}
+
+#[derive(Debug, Default, Resource)]
+struct PipelineCacheMeasurements {
+    queued: AtomicUsize,
+    creating: AtomicUsize,
+    ready: AtomicUsize,
+    failed: AtomicUsize,
+}
+
+fn add_pipeline_cache_measurement(
+    mut diagnostics: Diagnostics,
+    measurements: Res<PipelineCacheMeasurements>,
+) {
+    diagnostics.add_measurement(&PIPELINE_CACHE_QUEUED, || {
+        measurements.queued.load(Ordering::Relaxed) as f64
+    });
+    diagnostics.add_measurement(&PIPELINE_CACHE_CREATING, || {
+        measurements.creating.load(Ordering::Relaxed) as f64
+    });
+    diagnostics.add_measurement(&PIPELINE_CACHE_READY, || {
+        measurements.ready.load(Ordering::Relaxed) as f64
+    });
+    diagnostics.add_measurement(&PIPELINE_CACHE_FAILED, || {
+        measurements.failed.load(Ordering::Relaxed) as f64
+    });
+}
+
+fn measure_pipeline_cache(
+    measurements: Extract<Res<PipelineCacheMeasurements>>,
+    pipeline_cache: Res<PipelineCache>,
+) {
+    let stats = pipeline_cache.stats();
+    measurements.queued.store(stats.queued, Ordering::Relaxed);
+    measurements
+        .creating
+        .store(stats.creating, Ordering::Relaxed);
+    measurements.ready.store(stats.ready, Ordering::Relaxed);
+    measurements.failed.store(stats.errored, Ordering::Relaxed);
+}
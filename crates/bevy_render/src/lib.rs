@@ -49,6 +49,8 @@ pub mod globals;
 pub mod gpu_component_array_buffer;
 pub mod gpu_readback;
 pub mod mesh;
+#[cfg(feature = "serialize")]
+pub mod pipeline_precache;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod pipelined_rendering;
 pub mod render_asset;
@@ -81,7 +83,11 @@ use crate::{
     gpu_readback::GpuReadbackPlugin,
     mesh::{MeshRenderAssetPlugin, RenderMesh},
     render_asset::prepare_assets,
-    render_resource::{init_empty_bind_group_layout, PipelineCache},
+    render_resource::{
+        init_empty_bind_group_layout, update_pipeline_compilation_metrics, PipelineCache,
+        PipelineCompilationEvent, PipelineCompilationRate, PipelineCompilationStats, PipelineReady,
+        SharedPipelineCompilationMetrics, SynchronousPipelineCompilation,
+    },
     renderer::{render_system, RenderAdapterInfo},
     settings::RenderCreation,
     storage::StoragePlugin,
@@ -90,9 +96,10 @@ use crate::{
 };
 use alloc::sync::Arc;
 use batching::gpu_preprocessing::BatchingPlugin;
-use bevy_app::{App, AppLabel, Plugin, SubApp};
+use bevy_app::{App, AppLabel, Last, Plugin, SubApp};
 use bevy_asset::{AssetApp, AssetServer};
 use bevy_ecs::{
+    message::message_update_system,
     prelude::*,
     schedule::{ScheduleBuildSettings, ScheduleLabel},
 };
@@ -101,7 +108,10 @@ use bevy_shader::{load_shader_library, Shader, ShaderLoader};
 use bevy_utils::prelude::default;
 use bevy_window::{PrimaryWindow, RawHandleWrapperHolder};
 use bitflags::bitflags;
-use core::ops::{Deref, DerefMut};
+use core::{
+    num::NonZeroUsize,
+    ops::{Deref, DerefMut},
+};
 use experimental::occlusion_culling::OcclusionCullingPlugin;
 use globals::GlobalsPlugin;
 use render_asset::{
@@ -125,7 +135,22 @@ pub struct RenderPlugin {
     pub render_creation: RenderCreation,
     /// If `true`, disables asynchronous pipeline compilation.
     /// This has no effect on macOS, Wasm, iOS, or without the `multi_threaded` feature.
+    ///
+    /// This is only the initial value, which can be changed at runtime through the
+    /// [`SynchronousPipelineCompilation`] resource.
     pub synchronous_pipeline_compilation: bool,
+    /// The contents of the driver-level pipeline cache of a previous run, as returned by
+    /// [`PipelineCache::pipeline_cache_data()`].
+    ///
+    /// Saving this blob on exit and passing it back on the next start lets drivers that support
+    /// [`PIPELINE_CACHE`](wgpu::Features::PIPELINE_CACHE) skip compiling the same pipelines
+    /// again. It is ignored if it was created by another adapter or driver version.
+    pub pipeline_cache_data: Option<Vec<u8>>,
+    /// The maximum number of pipelines compiled at the same time, or `None` to not limit them.
+    ///
+    /// Limiting compilations avoids flooding the task pool and spiking memory usage when hundreds
+    /// of pipelines are queued at once, for example at startup.
+    pub max_concurrent_compilations: Option<NonZeroUsize>,
     /// Debugging flags that can optionally be set when constructing the renderer.
     pub debug_flags: RenderDebugFlags,
 }
@@ -379,7 +404,10 @@ impl Plugin for RenderPlugin {
             diagnostic::RenderDiagnosticsPlugin,
         ));
 
-        app.init_resource::<RenderAssetBytesPerFrame>();
+        app.init_resource::<RenderAssetBytesPerFrame>()
+            .insert_resource(SynchronousPipelineCompilation(
+                self.synchronous_pipeline_compilation,
+            ));
         if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
             render_app.init_resource::<RenderAssetBytesPerFrameLimiter>();
             render_app
@@ -436,6 +464,8 @@ impl Plugin for RenderPlugin {
                     device.clone(),
                     render_adapter.clone(),
                     self.synchronous_pipeline_compilation,
+                    self.pipeline_cache_data.clone(),
+                    self.max_concurrent_compilations,
                 ))
                 .insert_resource(device)
                 .insert_resource(queue)
@@ -470,6 +500,13 @@ fn extract(main_world: &mut World, render_world: &mut World) {
 unsafe fn initialize_render_app(app: &mut App) {
     app.init_resource::<ScratchMainWorld>();
 
+    // The compilation metrics of the render world's pipeline cache are read in the main world.
+    let compilation_metrics = SharedPipelineCompilationMetrics::default();
+    app.init_resource::<PipelineCompilationRate>()
+        .init_resource::<PipelineCompilationStats>()
+        .insert_resource(compilation_metrics.clone())
+        .add_systems(Last, update_pipeline_compilation_metrics);
+
     let mut render_app = SubApp::new();
     render_app.update_schedule = Some(Render.intern());
 
@@ -486,8 +523,19 @@ unsafe fn initialize_render_app(app: &mut App) {
         .add_schedule(extract_schedule)
         .add_schedule(Render::base_schedule())
         .init_resource::<render_graph::RenderGraph>()
+        .insert_resource(compilation_metrics)
+        .add_message::<PipelineReady>()
+        .add_message::<PipelineCompilationEvent>()
         .insert_resource(app.world().resource::<AssetServer>().clone())
-        .add_systems(ExtractSchedule, PipelineCache::extract_shaders)
+        .add_systems(
+            ExtractSchedule,
+            (
+                PipelineCache::update_provided_shader_defs,
+                PipelineCache::extract_shaders,
+                PipelineCache::extract_synchronous_pipeline_compilation,
+            )
+                .chain(),
+        )
         .add_systems(
             Render,
             (
@@ -497,6 +545,9 @@ unsafe fn initialize_render_app(app: &mut App) {
                 (PipelineCache::process_pipeline_queue_system, render_system)
                     .chain()
                     .in_set(RenderSystems::Render),
+                PipelineCache::share_compilation_metrics_system.in_set(RenderSystems::Cleanup),
+                // The render world has no `First` schedule, so its messages are updated here.
+                message_update_system.in_set(RenderSystems::Cleanup),
                 despawn_temporary_render_entities.in_set(RenderSystems::PostCleanup),
             ),
         );
@@ -0,0 +1,235 @@
+//! Precaching of pipelines at startup, to avoid shader compilation stutter during gameplay.
+//!
+//! A manifest of the pipelines an app uses can be recorded during a profiling run with
+//! [`PipelinePrecachePlugin::record_path`] set, and shipped with the app as an asset. On startup,
+//! the [`PipelinePrecachePlugin`] then queues every pipeline of the manifest, so that they are
+//! created before they are first needed.
+//!
+//! This module is only available with the `serialize` feature.
+
+use crate::{
+    render_resource::{
+        PipelineCache, PipelineReady, ReadyPipelineId, SerializedComputePipelineDescriptor,
+        SerializedPipelineDescriptor, SerializedRenderPipelineDescriptor,
+    },
+    Extract, ExtractSchedule, Render, RenderApp, RenderSystems,
+};
+use bevy_app::{App, AppExit, Plugin};
+use bevy_asset::{
+    io::Reader, Asset, AssetApp, AssetLoader, AssetServer, Assets, Handle, LoadContext,
+};
+use bevy_ecs::{
+    message::MessageReader,
+    resource::Resource,
+    schedule::IntoScheduleConfigs,
+    system::{Local, Res, ResMut},
+};
+use bevy_reflect::TypePath;
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::PathBuf};
+use thiserror::Error;
+use tracing::{info, warn};
+
+/// Queues the pipelines listed in a [`PipelineCacheManifest`] at startup.
+///
+/// The manifest is loaded as an asset, so its pipelines are queued once it has finished loading.
+/// They are created from their full recorded descriptors, exactly like the pipelines that will
+/// later use them.
+pub struct PipelinePrecachePlugin {
+    /// The asset path of the manifest to load, with a `.pipelines.ron` extension.
+    pub manifest_path: String,
+    /// If set, records all pipelines created during this run and writes them to this file when the
+    /// app exits.
+    ///
+    /// This includes the pipelines queued from the loaded manifest, so a recorded manifest can
+    /// replace the one it was started with.
+    pub record_path: Option<PathBuf>,
+}
+
+impl Plugin for PipelinePrecachePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<PipelineCacheManifest>()
+            .register_asset_loader(PipelineCacheManifestLoader);
+
+        let manifest = app
+            .world()
+            .resource::<AssetServer>()
+            .load(self.manifest_path.clone());
+        app.insert_resource(PrecachedPipelineManifest(manifest));
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app.add_systems(ExtractSchedule, queue_precached_pipelines);
+
+        if let Some(record_path) = &self.record_path {
+            render_app
+                .insert_resource(PipelineCacheManifestBuilder::new(record_path.clone()))
+                .add_systems(ExtractSchedule, save_recorded_pipelines_on_exit)
+                .add_systems(
+                    Render,
+                    record_compiled_pipelines.in_set(RenderSystems::Cleanup),
+                );
+        }
+    }
+}
+
+/// A list of pipelines to create at startup, see [`PipelinePrecachePlugin`].
+#[derive(Asset, TypePath, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct PipelineCacheManifest {
+    pub pipelines: Vec<SerializedPipelineDescriptor>,
+}
+
+impl PipelineCacheManifest {
+    /// Writes the manifest to a RON file.
+    pub fn save(&self, path: &std::path::Path) -> Result<(), PipelineManifestError> {
+        let manifest = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        fs::write(path, manifest)?;
+        Ok(())
+    }
+}
+
+/// An [`AssetLoader`] for [`PipelineCacheManifest`]s.
+#[derive(Default)]
+pub struct PipelineCacheManifestLoader;
+
+impl AssetLoader for PipelineCacheManifestLoader {
+    type Asset = PipelineCacheManifest;
+    type Settings = ();
+    type Error = PipelineManifestError;
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<PipelineCacheManifest, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["pipelines.ron"]
+    }
+}
+
+/// An error while loading or saving a [`PipelineCacheManifest`].
+#[derive(Error, Debug)]
+pub enum PipelineManifestError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Deserialize(#[from] ron::error::SpannedError),
+    #[error(transparent)]
+    Serialize(#[from] ron::Error),
+}
+
+/// Records the pipelines created by the [`PipelineCache`] into a [`PipelineCacheManifest`].
+///
+/// Only pipelines whose shaders were loaded from an asset path can be recorded.
+#[derive(Resource)]
+pub struct PipelineCacheManifestBuilder {
+    manifest: PipelineCacheManifest,
+    /// The path the manifest is written to when the app exits.
+    save_path: PathBuf,
+}
+
+impl PipelineCacheManifestBuilder {
+    /// Creates an empty builder that writes its manifest to `save_path` when the app exits.
+    pub fn new(save_path: PathBuf) -> Self {
+        Self {
+            manifest: PipelineCacheManifest::default(),
+            save_path,
+        }
+    }
+
+    /// Records a pipeline, returning `true` if it wasn't recorded before.
+    pub fn record(&mut self, pipeline_cache: &PipelineCache, id: ReadyPipelineId) -> bool {
+        let pipeline = match id {
+            ReadyPipelineId::Render(id) => SerializedRenderPipelineDescriptor::new(
+                pipeline_cache.get_render_pipeline_descriptor(id),
+            )
+            .map(SerializedPipelineDescriptor::Render),
+            ReadyPipelineId::Compute(id) => SerializedComputePipelineDescriptor::new(
+                pipeline_cache.get_compute_pipeline_descriptor(id),
+            )
+            .map(SerializedPipelineDescriptor::Compute),
+        };
+
+        let Some(pipeline) = pipeline else {
+            return false;
+        };
+        if self.manifest.pipelines.contains(&pipeline) {
+            return false;
+        }
+        self.manifest.pipelines.push(pipeline);
+        true
+    }
+
+    /// The manifest of all recorded pipelines.
+    pub fn manifest(&self) -> &PipelineCacheManifest {
+        &self.manifest
+    }
+
+    /// Writes the manifest of all recorded pipelines to its save path.
+    ///
+    /// This happens automatically when the app exits, but can be triggered earlier, for example
+    /// from a debug key binding.
+    pub fn save(&self) -> Result<(), PipelineManifestError> {
+        self.manifest.save(&self.save_path)
+    }
+}
+
+/// The manifest loaded by the [`PipelinePrecachePlugin`].
+#[derive(Resource)]
+struct PrecachedPipelineManifest(Handle<PipelineCacheManifest>);
+
+fn queue_precached_pipelines(
+    manifest: Extract<Option<Res<PrecachedPipelineManifest>>>,
+    manifests: Extract<Res<Assets<PipelineCacheManifest>>>,
+    pipeline_cache: Res<PipelineCache>,
+    asset_server: Res<AssetServer>,
+    mut queued: Local<bool>,
+) {
+    if *queued {
+        return;
+    }
+    let Some(manifest) = manifest
+        .as_ref()
+        .and_then(|manifest| manifests.get(&manifest.0))
+    else {
+        return;
+    };
+
+    if !manifest.pipelines.is_empty() {
+        info!("Precaching {} pipelines", manifest.pipelines.len());
+    }
+    pipeline_cache.queue_from_serialized_descriptors(manifest.pipelines.clone(), &asset_server);
+    *queued = true;
+}
+
+fn record_compiled_pipelines(
+    mut pipeline_ready: MessageReader<PipelineReady>,
+    pipeline_cache: Res<PipelineCache>,
+    mut builder: ResMut<PipelineCacheManifestBuilder>,
+) {
+    for PipelineReady(id) in pipeline_ready.read() {
+        builder.record(&pipeline_cache, *id);
+    }
+}
+
+fn save_recorded_pipelines_on_exit(
+    mut app_exit: Extract<MessageReader<AppExit>>,
+    builder: Res<PipelineCacheManifestBuilder>,
+) {
+    if app_exit.read().last().is_none() {
+        return;
+    }
+    if let Err(err) = builder.save() {
+        warn!(
+            "Failed to save the pipeline manifest {}: {err}",
+            builder.save_path.display()
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{
+    render_resource::{Buffer, BufferDescriptor, BufferUsages},
+    renderer::RenderDevice,
+};
+use alloc::sync::Arc;
+use bevy_ecs::resource::Resource;
+use bevy_platform::collections::HashMap;
+use core::ops::Deref;
+use std::sync::{Mutex, PoisonError};
+
+/// Reuses transient [`Buffer`]s across frames, instead of creating and destroying them every
+/// frame.
+///
+/// Buffers are pooled by size and usage. A buffer acquired with [`BufferPool::acquire()`] returns
+/// to the pool when its [`PooledBuffer`] is dropped, and can be acquired again from the next frame
+/// on, once the commands of the frame it was used in were submitted. Buffers that weren't used for
+/// [`BufferPool::max_unused_frames`] frames are destroyed by [`BufferPool::flush_unused()`].
+///
+/// Pools are created with [`RenderDevice::create_buffer_pool()`]. Their owner is responsible for
+/// calling [`BufferPool::flush_unused()`] once per frame, for example during
+/// [`RenderSystems::Cleanup`](crate::RenderSystems::Cleanup).
+#[derive(Resource, Clone)]
+pub struct BufferPool {
+    device: RenderDevice,
+    max_unused_frames: u64,
+    inner: Arc<Mutex<BufferPoolInner>>,
+}
+
+type BufferPoolKey = (wgpu::BufferAddress, BufferUsages);
+
+#[derive(Default)]
+struct BufferPoolInner {
+    /// The free buffers of each bucket, along with the frame during which they were released.
+    free: HashMap<BufferPoolKey, Vec<(Buffer, u64)>>,
+    frame: u64,
+}
+
+impl BufferPool {
+    /// A reasonable number of frames after which a [`BufferPool`] destroys unused buffers.
+    pub const DEFAULT_MAX_UNUSED_FRAMES: u64 = 3;
+
+    /// Creates an empty pool, destroying buffers that weren't used for `max_unused_frames`.
+    pub fn new(device: RenderDevice, max_unused_frames: u64) -> Self {
+        Self {
+            device,
+            max_unused_frames,
+            inner: Arc::new(Mutex::new(BufferPoolInner::default())),
+        }
+    }
+
+    /// The number of frames after which unused buffers are destroyed.
+    pub fn max_unused_frames(&self) -> u64 {
+        self.max_unused_frames
+    }
+
+    /// Returns a free buffer matching the size and usage of `desc`, or creates one.
+    ///
+    /// The label of `desc` is only used if a new buffer is created.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `desc` requests the buffer to be mapped at creation, since reused buffers can't
+    /// be.
+    pub fn acquire(&self, desc: &BufferDescriptor) -> PooledBuffer {
+        assert!(
+            !desc.mapped_at_creation,
+            "pooled buffers can't be mapped at creation"
+        );
+        let key = (desc.size, desc.usage);
+        let reused = {
+            let mut inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+            let frame = inner.frame;
+            inner.free.get_mut(&key).and_then(|free| {
+                // Buffers released during this frame may still be used by its commands.
+                let index = free
+                    .iter()
+                    .position(|(_, released_frame)| *released_frame < frame)?;
+                Some(free.swap_remove(index).0)
+            })
+        };
+        let buffer = reused.unwrap_or_else(|| self.device.create_buffer(desc));
+        PooledBuffer {
+            buffer: Some(buffer),
+            key,
+            pool: self.inner.clone(),
+        }
+    }
+
+    /// Advances the pool to the next frame, and destroys the buffers that weren't used for
+    /// [`BufferPool::max_unused_frames`] frames.
+    pub fn flush_unused(&self) {
+        let mut inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        inner.frame += 1;
+        let oldest_frame = inner.frame.saturating_sub(self.max_unused_frames);
+        inner.free.retain(|_, free| {
+            free.retain(|(buffer, released_frame)| {
+                let keep = *released_frame >= oldest_frame;
+                if !keep {
+                    buffer.destroy();
+                }
+                keep
+            });
+            !free.is_empty()
+        });
+    }
+
+    /// The number of free buffers in the pool.
+    pub fn free_buffer_count(&self) -> usize {
+        let inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        inner.free.values().map(Vec::len).sum()
+    }
+}
+
+/// A [`Buffer`] acquired from a [`BufferPool`], which returns to the pool when dropped.
+pub struct PooledBuffer {
+    buffer: Option<Buffer>,
+    key: BufferPoolKey,
+    pool: Arc<Mutex<BufferPoolInner>>,
+}
+
+impl Deref for PooledBuffer {
+    type Target = Buffer;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        // Only taken when dropped.
+        self.buffer.as_ref().unwrap()
+    }
+}
+
+impl Drop for PooledBuffer {
+    fn drop(&mut self) {
+        if let Some(buffer) = self.buffer.take() {
+            let mut inner = self.pool.lock().unwrap_or_else(PoisonError::into_inner);
+            let frame = inner.frame;
+            inner
+                .free
+                .entry(self.key)
+                .or_default()
+                .push((buffer, frame));
+        }
+    }
+}
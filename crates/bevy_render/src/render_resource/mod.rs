@@ -5,12 +5,17 @@ mod bind_group_layout;
 mod bind_group_layout_entries;
 mod bindless;
 mod buffer;
+mod buffer_pool;
 mod buffer_vec;
 mod gpu_array_buffer;
 mod pipeline;
 mod pipeline_cache;
+mod pipeline_fallback;
 mod pipeline_specializer;
+mod render_bundle;
 pub mod resource_macros;
+#[cfg(feature = "serialize")]
+mod serialized_pipeline;
 mod specializer;
 mod storage_buffer;
 mod texture;
@@ -22,11 +27,16 @@ pub use bind_group_layout::*;
 pub use bind_group_layout_entries::*;
 pub use bindless::*;
 pub use buffer::*;
+pub use buffer_pool::*;
 pub use buffer_vec::*;
 pub use gpu_array_buffer::*;
 pub use pipeline::*;
 pub use pipeline_cache::*;
+pub use pipeline_fallback::*;
 pub use pipeline_specializer::*;
+pub use render_bundle::*;
+#[cfg(feature = "serialize")]
+pub use serialized_pipeline::*;
 pub use specializer::*;
 pub use storage_buffer::*;
 pub use texture::*;
@@ -4,7 +4,9 @@ use alloc::borrow::Cow;
 use bevy_asset::Handle;
 use bevy_mesh::VertexBufferLayout;
 use bevy_shader::{Shader, ShaderDefVal};
+use core::hash::{Hash, Hasher};
 use core::iter;
+use core::num::NonZeroU32;
 use core::ops::Deref;
 use thiserror::Error;
 use wgpu::{
@@ -104,9 +106,11 @@ impl BindGroupLayoutDescriptor {
 }
 
 /// Describes a render (graphics) pipeline.
-#[derive(Clone, Debug, PartialEq, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct RenderPipelineDescriptor {
     /// Debug label of the pipeline. This will show up in graphics debuggers for easy identification.
+    ///
+    /// If `None`, a label prefixed with `auto:` is generated from the asset paths of the shaders.
     pub label: Option<Cow<'static, str>>,
     /// The layout of bind groups for this pipeline.
     pub layout: Vec<BindGroupLayoutDescriptor>,
@@ -123,6 +127,14 @@ pub struct RenderPipelineDescriptor {
     pub multisample: MultisampleState,
     /// The compiled fragment stage, its entry point, and the color targets.
     pub fragment: Option<FragmentState>,
+    /// If the pipeline will be used with a multiview render pass, this indicates how many array
+    /// layers the attachments will have. Requires [`Features::MULTIVIEW`](wgpu::Features::MULTIVIEW).
+    ///
+    /// When set, the `MULTIVIEW` shader def is added to both stages.
+    pub multiview: Option<NonZeroU32>,
+    /// Values for pipeline-overridable constants (`override` declarations in WGSL), keyed by
+    /// their name or numeric `@id`. These apply to both the vertex and fragment stages.
+    pub constants: Vec<(String, f64)>,
     /// Whether to zero-initialize workgroup memory by default. If you're not sure, set this to true.
     /// If this is false, reading from workgroup variables before writing to them will result in garbage values.
     pub zero_initialize_workgroup_memory: bool,
@@ -142,20 +154,23 @@ impl RenderPipelineDescriptor {
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Default)]
 pub struct VertexState {
     /// The compiled shader module for this stage.
     pub shader: Handle<Shader>,
     pub shader_defs: Vec<ShaderDefVal>,
     /// The name of the entry point in the compiled shader, or `None` if the default entry point
     /// is used.
+    ///
+    /// A shader can define several vertex entry points, for example for skinned and non-skinned
+    /// meshes. Selecting one of them only requires changing this field, not a separate shader.
     pub entry_point: Option<Cow<'static, str>>,
     /// The format of any vertex buffers used with this pipeline.
     pub buffers: Vec<VertexBufferLayout>,
 }
 
 /// Describes the fragment process in a render pipeline.
-#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Default)]
 pub struct FragmentState {
     /// The compiled shader module for this stage.
     pub shader: Handle<Shader>,
@@ -174,8 +189,11 @@ impl FragmentState {
 }
 
 /// Describes a compute pipeline.
-#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct ComputePipelineDescriptor {
+    /// Debug label of the pipeline. This will show up in graphics debuggers for easy identification.
+    ///
+    /// If `None`, a label prefixed with `auto:` is generated from the asset path of the shader.
     pub label: Option<Cow<'static, str>>,
     pub layout: Vec<BindGroupLayoutDescriptor>,
     pub push_constant_ranges: Vec<PushConstantRange>,
@@ -185,11 +203,93 @@ pub struct ComputePipelineDescriptor {
     /// The name of the entry point in the compiled shader, or `None` if the default entry point
     /// is used.
     pub entry_point: Option<Cow<'static, str>>,
+    /// Values for pipeline-overridable constants (`override` declarations in WGSL), keyed by
+    /// their name or numeric `@id`.
+    pub constants: Vec<(String, f64)>,
     /// Whether to zero-initialize workgroup memory by default. If you're not sure, set this to true.
     /// If this is false, reading from workgroup variables before writing to them will result in garbage values.
     pub zero_initialize_workgroup_memory: bool,
 }
 
+// `f64` is neither `Eq` nor `Hash`, so the values of the overridable constants are compared and
+// hashed by their bits.
+impl PartialEq for RenderPipelineDescriptor {
+    fn eq(&self, other: &Self) -> bool {
+        self.label == other.label
+            && self.layout == other.layout
+            && self.push_constant_ranges == other.push_constant_ranges
+            && self.vertex == other.vertex
+            && self.primitive == other.primitive
+            && self.depth_stencil == other.depth_stencil
+            && self.multisample == other.multisample
+            && self.fragment == other.fragment
+            && self.multiview == other.multiview
+            && constants_eq(&self.constants, &other.constants)
+            && self.zero_initialize_workgroup_memory == other.zero_initialize_workgroup_memory
+    }
+}
+
+impl PartialEq for ComputePipelineDescriptor {
+    fn eq(&self, other: &Self) -> bool {
+        self.label == other.label
+            && self.layout == other.layout
+            && self.push_constant_ranges == other.push_constant_ranges
+            && self.shader == other.shader
+            && self.shader_defs == other.shader_defs
+            && self.entry_point == other.entry_point
+            && constants_eq(&self.constants, &other.constants)
+            && self.zero_initialize_workgroup_memory == other.zero_initialize_workgroup_memory
+    }
+}
+
+impl Eq for ComputePipelineDescriptor {}
+
+impl Hash for RenderPipelineDescriptor {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.label.hash(state);
+        self.layout.hash(state);
+        self.push_constant_ranges.hash(state);
+        self.vertex.hash(state);
+        self.primitive.hash(state);
+        self.depth_stencil.hash(state);
+        self.multisample.hash(state);
+        self.fragment.hash(state);
+        self.multiview.hash(state);
+        hash_constants(&self.constants, state);
+        self.zero_initialize_workgroup_memory.hash(state);
+    }
+}
+
+impl Hash for ComputePipelineDescriptor {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.label.hash(state);
+        self.layout.hash(state);
+        self.push_constant_ranges.hash(state);
+        self.shader.hash(state);
+        self.shader_defs.hash(state);
+        self.entry_point.hash(state);
+        hash_constants(&self.constants, state);
+        self.zero_initialize_workgroup_memory.hash(state);
+    }
+}
+
+fn constants_eq(a: &[(String, f64)], b: &[(String, f64)]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .all(|((a_name, a_value), (b_name, b_value))| {
+                a_name == b_name && a_value.to_bits() == b_value.to_bits()
+            })
+}
+
+fn hash_constants<H: Hasher>(constants: &[(String, f64)], state: &mut H) {
+    state.write_usize(constants.len());
+    for (name, value) in constants {
+        name.hash(state);
+        value.to_bits().hash(state);
+    }
+}
+
 // utility function to set a value at the specified index, extending with
 // a filler value if the index is out of bounds.
 fn filling_set_at<T: Clone>(vec: &mut Vec<T>, index: usize, filler: T, value: T) {
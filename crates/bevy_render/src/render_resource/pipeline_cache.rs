@@ -3,21 +3,36 @@ use crate::{
     renderer::{RenderAdapter, RenderDevice, WgpuWrapper},
     Extract,
 };
-use alloc::{borrow::Cow, sync::Arc};
+use alloc::{borrow::Cow, collections::VecDeque, sync::Arc};
+use async_channel::Sender;
 use bevy_asset::{AssetEvent, AssetId, Assets, Handle};
 use bevy_ecs::{
-    message::MessageReader,
+    message::{Message, MessageReader, MessageWriter},
     resource::Resource,
-    system::{Res, ResMut},
+    system::{Res, ResMut, System},
+    world::{Mut, World},
+};
+use bevy_platform::{
+    collections::{HashMap, HashSet},
+    hash::FixedHasher,
+    time::Instant,
 };
-use bevy_platform::collections::{HashMap, HashSet};
 use bevy_shader::{
-    CachedPipelineId, PipelineCacheError, Shader, ShaderCache, ShaderCacheSource, ShaderDefVal,
-    ValidateShader,
+    CachedPipelineId, PipelineCacheError, Shader, ShaderCache, ShaderCacheSource,
+    ShaderDefConflictPolicy, ShaderDefVal, ShaderImport, ValidateShader,
 };
 use bevy_tasks::Task;
 use bevy_utils::default;
-use core::{future::Future, hash::Hash, mem};
+use core::{
+    any::TypeId,
+    future::Future,
+    hash::{BuildHasher, Hash},
+    mem,
+    num::NonZeroUsize,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+use parking_lot::RwLock;
 use std::sync::{Mutex, PoisonError};
 use tracing::error;
 use wgpu::{PipelineCompilationOptions, VertexBufferLayout as RawVertexBufferLayout};
@@ -41,9 +56,17 @@ pub enum Pipeline {
 }
 
 /// Index of a cached render pipeline in a [`PipelineCache`].
+///
+/// The [`Default`] value is [`CachedRenderPipelineId::INVALID`], which doesn't identify any pipeline.
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
 pub struct CachedRenderPipelineId(CachedPipelineId);
 
+impl Default for CachedRenderPipelineId {
+    fn default() -> Self {
+        Self::INVALID
+    }
+}
+
 impl CachedRenderPipelineId {
     /// An invalid cached render pipeline index, often used to initialize a variable.
     pub const INVALID: Self = CachedRenderPipelineId(usize::MAX);
@@ -55,9 +78,17 @@ impl CachedRenderPipelineId {
 }
 
 /// Index of a cached compute pipeline in a [`PipelineCache`].
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+///
+/// The [`Default`] value is [`CachedComputePipelineId::INVALID`], which doesn't identify any pipeline.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
 pub struct CachedComputePipelineId(CachedPipelineId);
 
+impl Default for CachedComputePipelineId {
+    fn default() -> Self {
+        Self::INVALID
+    }
+}
+
 impl CachedComputePipelineId {
     /// An invalid cached compute pipeline index, often used to initialize a variable.
     pub const INVALID: Self = CachedComputePipelineId(usize::MAX);
@@ -68,29 +99,175 @@ impl CachedComputePipelineId {
     }
 }
 
+/// The ID of a pipeline that was created successfully, see [`PipelineReady`].
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum ReadyPipelineId {
+    Render(CachedRenderPipelineId),
+    Compute(CachedComputePipelineId),
+}
+
+impl ReadyPipelineId {
+    fn new(id: CachedPipelineId, descriptor: &PipelineDescriptor) -> Self {
+        match descriptor {
+            PipelineDescriptor::RenderPipelineDescriptor(_) => {
+                ReadyPipelineId::Render(CachedRenderPipelineId(id))
+            }
+            PipelineDescriptor::ComputePipelineDescriptor(_) => {
+                ReadyPipelineId::Compute(CachedComputePipelineId(id))
+            }
+        }
+    }
+}
+
+/// A [`Message`] written in the render world when a pipeline of the [`PipelineCache`] transitions
+/// to [`CachedPipelineState::Ok`].
+///
+/// This lets systems react once to a pipeline becoming available, instead of polling its state
+/// every frame.
+#[derive(Message, Copy, Clone, Debug)]
+pub struct PipelineReady(pub ReadyPipelineId);
+
+/// A [`Message`] written in the render world when the [`PipelineCache`] starts or finishes
+/// creating a pipeline GPU object, for example to draw a timeline of shader compilation in a
+/// profiler.
+#[derive(Message, Copy, Clone, Debug)]
+pub struct PipelineCompilationEvent {
+    pub id: CachedPipelineId,
+    pub phase: CompilationPhase,
+    /// When the phase was reached. For pipelines created in a background task, this is when the
+    /// cache noticed the task finished, which may be up to a frame after it actually did.
+    pub timestamp: Instant,
+}
+
+/// The phase of pipeline creation a [`PipelineCompilationEvent`] reports.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CompilationPhase {
+    /// The creation of the pipeline GPU object started.
+    Started,
+    /// The pipeline GPU object was created.
+    Completed,
+    /// The creation of the pipeline GPU object failed, including with errors that are retried,
+    /// such as a shader not being loaded yet.
+    Failed,
+}
+
 pub struct CachedPipeline {
     pub descriptor: PipelineDescriptor,
     pub state: CachedPipelineState,
+    /// The frame during which the pipeline was last retrieved from the cache.
+    last_accessed_frame: AtomicU64,
+    /// When the creation of the pipeline GPU object was started.
+    pub compilation_started_at: Option<Instant>,
+    /// How long the creation of the pipeline GPU object took, including shader processing, set
+    /// once the pipeline was created or failed to be created.
+    ///
+    /// Sorting [`PipelineCache::pipelines()`] by this duration shows which pipelines are the most
+    /// expensive to compile.
+    pub compilation_duration: Option<Duration>,
+    /// Whether the pipeline is compiled synchronously or asynchronously.
+    pub compilation_hint: CompilationHint,
+    /// The count of [`PipelineCompilationStats`] the pipeline is included in.
+    stats_category: PipelineStatsCategory,
+}
+
+impl CachedPipeline {
+    fn new(descriptor: PipelineDescriptor, frame: u64) -> Self {
+        Self {
+            descriptor,
+            state: CachedPipelineState::Queued,
+            last_accessed_frame: AtomicU64::new(frame),
+            compilation_started_at: None,
+            compilation_duration: None,
+            compilation_hint: CompilationHint::SystemDefault,
+            stats_category: PipelineStatsCategory::Queued,
+        }
+    }
+
+    /// Moves the pipeline to the count of its current state in `stats`.
+    fn update_stats(&mut self, stats: &mut PipelineCompilationStats) {
+        let category = PipelineStatsCategory::of(&self.state);
+        if category != self.stats_category {
+            *self.stats_category.count(stats) -= 1;
+            *category.count(stats) += 1;
+            self.stats_category = category;
+        }
+    }
+
+    fn compilation_finished(&mut self) {
+        self.compilation_duration = self
+            .compilation_started_at
+            .map(|started_at| started_at.elapsed());
+    }
+
+    /// Ends the creation of a pipeline GPU object replacing the one of a hot reloaded pipeline,
+    /// returning whether it [`CompilationPhase::Completed`] or [`CompilationPhase::Failed`].
+    ///
+    /// The previous pipeline is only dropped once its replacement was created. On errors that
+    /// are retried it stays [`CachedPipelineState::HotReloading`] and is queued again, on other
+    /// errors the error is logged and the previous pipeline is kept in use.
+    fn finish_hot_reload(
+        &mut self,
+        result: Result<Pipeline, PipelineCacheError>,
+    ) -> CompilationPhase {
+        self.compilation_finished();
+        let CachedPipelineState::HotReloading(_, previous) =
+            mem::replace(&mut self.state, CachedPipelineState::Queued)
+        else {
+            unreachable!("the pipeline is hot reloading");
+        };
+        match result {
+            Ok(pipeline) => {
+                self.state = CachedPipelineState::Ok(pipeline);
+                return CompilationPhase::Completed;
+            }
+            Err(
+                PipelineCacheError::ShaderNotLoaded(..)
+                | PipelineCacheError::ShaderImportNotYetAvailable,
+            ) => {
+                self.state = CachedPipelineState::HotReloading(None, previous);
+            }
+            Err(err) => {
+                error!(
+                    "failed to hot reload pipeline, keeping the previous one: {}\n{}",
+                    err,
+                    pipeline_error_context(&self.descriptor)
+                );
+                self.state = CachedPipelineState::Ok(previous);
+            }
+        }
+        CompilationPhase::Failed
+    }
 }
 
 /// State of a cached pipeline inserted into a [`PipelineCache`].
-#[cfg_attr(
-    not(target_arch = "wasm32"),
-    expect(
-        clippy::large_enum_variant,
-        reason = "See https://github.com/bevyengine/bevy/issues/19220"
-    )
-)]
 #[derive(Debug)]
 pub enum CachedPipelineState {
     /// The pipeline GPU object is queued for creation.
     Queued,
     /// The pipeline GPU object is being created.
-    Creating(Task<Result<Pipeline, PipelineCacheError>>),
+    ///
+    /// The task is boxed to keep the size of this state down.
+    Creating(Box<Task<Result<Pipeline, PipelineCacheError>>>),
     /// The pipeline GPU object was created successfully and is available (allocated on the GPU).
     Ok(Pipeline),
     /// An error occurred while trying to create the pipeline GPU object.
     Err(PipelineCacheError),
+    /// A shader of the pipeline changed and a new pipeline GPU object is queued for creation
+    /// (`None`) or being created, while the previous one stays in use.
+    ///
+    /// The previous pipeline is only replaced once the new one was created successfully, so hot
+    /// reloading a shader doesn't leave a frame without the pipeline. If the new pipeline fails
+    /// to be created with an error that retrying won't fix, the error is logged and the previous
+    /// one is kept.
+    HotReloading(
+        Option<Box<Task<Result<Pipeline, PipelineCacheError>>>>,
+        Pipeline,
+    ),
+    /// The pipeline was removed from the cache and its GPU object was dropped.
+    ///
+    /// See [`PipelineCache::remove_render_pipeline()`] and
+    /// [`PipelineCache::remove_compute_pipeline()`].
+    Evicted,
 }
 
 impl CachedPipelineState {
@@ -104,9 +281,12 @@ impl CachedPipelineState {
     ///
     /// This method panics if the pipeline GPU object is not available, either because it is
     /// pending creation or because an error occurred while attempting to create GPU object.
+    /// While hot reloading, the previous pipeline GPU object is returned.
     pub fn unwrap(&self) -> &Pipeline {
         match self {
-            CachedPipelineState::Ok(pipeline) => pipeline,
+            CachedPipelineState::Ok(pipeline) | CachedPipelineState::HotReloading(_, pipeline) => {
+                pipeline
+            }
             CachedPipelineState::Queued => {
                 panic!("Pipeline has not been compiled yet. It is still in the 'Queued' state.")
             }
@@ -114,14 +294,356 @@ impl CachedPipelineState {
                 panic!("Pipeline has not been compiled yet. It is still in the 'Creating' state.")
             }
             CachedPipelineState::Err(err) => panic!("{}", err),
+            CachedPipelineState::Evicted => {
+                panic!("Pipeline has been removed from the cache.")
+            }
+        }
+    }
+
+    /// Returns `true` if the pipeline GPU object was created successfully. While hot reloading,
+    /// the previous pipeline GPU object is available, so this returns `true`.
+    #[inline]
+    pub fn is_ready(&self) -> bool {
+        matches!(
+            self,
+            CachedPipelineState::Ok(_) | CachedPipelineState::HotReloading(..)
+        )
+    }
+
+    /// Returns `true` if an error occurred while trying to create the pipeline GPU object.
+    #[inline]
+    pub fn is_failed(&self) -> bool {
+        matches!(self, CachedPipelineState::Err(_))
+    }
+
+    /// Returns `true` if the pipeline GPU object is queued for creation or being created. While
+    /// hot reloading, the previous pipeline GPU object is available, so this returns `false`.
+    #[inline]
+    pub fn is_pending(&self) -> bool {
+        matches!(
+            self,
+            CachedPipelineState::Queued | CachedPipelineState::Creating(_)
+        )
+    }
+
+    /// Returns the pipeline GPU object if it was created successfully, or `None` otherwise.
+    /// While hot reloading, the previous pipeline GPU object is returned.
+    ///
+    /// This is the non-panicking alternative to [`CachedPipelineState::unwrap()`].
+    #[inline]
+    pub fn as_ok(&self) -> Option<&Pipeline> {
+        match self {
+            CachedPipelineState::Ok(pipeline) | CachedPipelineState::HotReloading(_, pipeline) => {
+                Some(pipeline)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the pipeline GPU object was either created successfully, or failed to be
+    /// created. While hot reloading, the previous pipeline GPU object counts as created.
+    #[inline]
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            CachedPipelineState::Ok(_)
+                | CachedPipelineState::Err(_)
+                | CachedPipelineState::HotReloading(..)
+        )
+    }
+
+    /// Returns the created pipeline GPU object or the error that occurred while trying to create
+    /// it, or `None` if the pipeline is still pending creation or was evicted. While hot
+    /// reloading, the previous pipeline GPU object is returned.
+    #[inline]
+    pub fn as_result(&self) -> Option<Result<&Pipeline, &PipelineCacheError>> {
+        match self {
+            CachedPipelineState::Ok(pipeline) | CachedPipelineState::HotReloading(_, pipeline) => {
+                Some(Ok(pipeline))
+            }
+            CachedPipelineState::Err(err) => Some(Err(err)),
+            CachedPipelineState::Queued
+            | CachedPipelineState::Creating(_)
+            | CachedPipelineState::Evicted => None,
+        }
+    }
+
+    /// Converts the state into the created pipeline GPU object, or the error that occurred while
+    /// trying to create it.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the pipeline GPU object is still pending creation. While hot
+    /// reloading, the previous pipeline GPU object is returned.
+    pub fn into_result(self) -> Result<Pipeline, PipelineCacheError> {
+        match self {
+            CachedPipelineState::Ok(pipeline) | CachedPipelineState::HotReloading(_, pipeline) => {
+                Ok(pipeline)
+            }
+            CachedPipelineState::Err(err) => Err(err),
+            CachedPipelineState::Queued => {
+                panic!("Pipeline has not been compiled yet. It is still in the 'Queued' state.")
+            }
+            CachedPipelineState::Creating(..) => {
+                panic!("Pipeline has not been compiled yet. It is still in the 'Creating' state.")
+            }
+            CachedPipelineState::Evicted => {
+                panic!("Pipeline has been removed from the cache.")
+            }
+        }
+    }
+}
+
+/// A [`Resource`] that contributes shader defs to every shader processed by the
+/// [`PipelineCache`], such as defs derived from render settings.
+///
+/// See [`PipelineCache::register_shader_def_provider()`].
+pub trait ShaderDefProvider {
+    /// The shader defs to add to every shader.
+    fn shader_defs(&self) -> Vec<ShaderDefVal>;
+}
+
+/// Number of frames over which [`PipelineCache::compilation_rate()`] is averaged.
+const COMPILATION_RATE_WINDOW: usize = 60;
+
+/// The rate at which the [`PipelineCache`] finishes creating pipelines, in pipelines per second.
+///
+/// This main world resource is updated every frame during [`Last`] from
+/// [`PipelineCache::compilation_rate()`], as of the last frame the render world finished. It can
+/// be used to back off on queuing complex pipeline variants while compilation is falling behind.
+///
+/// [`Last`]: bevy_app::Last
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct PipelineCompilationRate(pub f32);
+
+/// Whether the [`PipelineCache`] compiles pipelines synchronously, in the main world.
+///
+/// This is inserted by the [`RenderPlugin`](crate::RenderPlugin) from its
+/// `synchronous_pipeline_compilation` setting, and changes to it are applied to the
+/// [`PipelineCache`] during extraction, so it can be toggled at runtime, for example from a debug
+/// menu. It has no effect on macOS, wasm, or without the `multi_threaded` feature.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SynchronousPipelineCompilation(pub bool);
+
+/// How the [`PipelineCache`] compiles a pipeline, see
+/// [`PipelineCache::queue_render_pipeline_with_hint()`].
+///
+/// Like [`SynchronousPipelineCompilation`], this has no effect on macOS, wasm, or without the
+/// `multi_threaded` feature, where pipelines are always compiled synchronously.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CompilationHint {
+    /// Compile the pipeline in a background task, without blocking the render world.
+    Async,
+    /// Compile the pipeline right away, blocking the render world until it's created.
+    ///
+    /// This suits pipelines needed as soon as possible, for example during startup.
+    Synchronous,
+    /// Follow the [`PipelineCache::synchronous_pipeline_compilation()`] setting.
+    #[default]
+    SystemDefault,
+}
+
+/// A snapshot of the number of pipelines in each [`CachedPipelineState`] of the
+/// [`PipelineCache`], along with the time spent creating pipelines.
+///
+/// This main world resource is updated every frame during [`Last`] from
+/// [`PipelineCache::stats()`], as of the last frame the render world finished.
+///
+/// [`Last`]: bevy_app::Last
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct PipelineCompilationStats {
+    /// Pipelines queued for creation, including those not yet processed by the cache.
+    pub queued: usize,
+    /// Pipelines being created.
+    pub creating: usize,
+    /// Pipelines created successfully.
+    pub ready: usize,
+    /// Pipelines that failed to be created.
+    pub errored: usize,
+    /// Pipelines that were removed from the cache.
+    pub evicted: usize,
+    /// Total time spent in pipeline creation tasks, in milliseconds, including shader processing.
+    pub total_compilation_time_ms: f64,
+}
+
+/// The [`PipelineCompilationRate`] and [`PipelineCompilationStats`] of the render world's
+/// [`PipelineCache`], shared with the main world.
+#[derive(Resource, Clone, Default)]
+pub(crate) struct SharedPipelineCompilationMetrics(
+    Arc<Mutex<(PipelineCompilationRate, PipelineCompilationStats)>>,
+);
+
+/// Copies the [`SharedPipelineCompilationMetrics`] to the main world resources.
+pub(crate) fn update_pipeline_compilation_metrics(
+    metrics: Res<SharedPipelineCompilationMetrics>,
+    mut compilation_rate: ResMut<PipelineCompilationRate>,
+    mut compilation_stats: ResMut<PipelineCompilationStats>,
+) {
+    (*compilation_rate, *compilation_stats) =
+        *metrics.0.lock().unwrap_or_else(PoisonError::into_inner);
+}
+
+/// The count of [`PipelineCompilationStats`] a pipeline is included in.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PipelineStatsCategory {
+    Queued,
+    Creating,
+    Ready,
+    Errored,
+    Evicted,
+}
+
+impl PipelineStatsCategory {
+    fn of(state: &CachedPipelineState) -> Self {
+        match state {
+            CachedPipelineState::Queued | CachedPipelineState::HotReloading(None, _) => {
+                Self::Queued
+            }
+            CachedPipelineState::Creating(_) | CachedPipelineState::HotReloading(Some(_), _) => {
+                Self::Creating
+            }
+            CachedPipelineState::Ok(_) => Self::Ready,
+            CachedPipelineState::Err(_) => Self::Errored,
+            CachedPipelineState::Evicted => Self::Evicted,
+        }
+    }
+
+    fn count(self, stats: &mut PipelineCompilationStats) -> &mut usize {
+        match self {
+            Self::Queued => &mut stats.queued,
+            Self::Creating => &mut stats.creating,
+            Self::Ready => &mut stats.ready,
+            Self::Errored => &mut stats.errored,
+            Self::Evicted => &mut stats.evicted,
+        }
+    }
+}
+
+/// How often pipelines reused an existing pipeline layout, see
+/// [`PipelineCache::pipeline_layout_cache_stats()`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PipelineLayoutCacheStats {
+    /// Lookups that reused an existing pipeline layout.
+    pub hits: u64,
+    /// Lookups that had to create a new pipeline layout.
+    pub misses: u64,
+}
+
+/// A callback run once a pipeline was created, see [`PipelineCache::on_pipeline_ready()`].
+type PipelineReadyCallback = Box<dyn FnOnce() + Send>;
+
+/// The number of shaders and shader modules in the [`PipelineCache`], see
+/// [`PipelineCache::shader_stats()`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ShaderCacheStats {
+    /// Distinct shaders known to the cache.
+    pub unique_shaders: usize,
+    /// Shader modules created from these shaders, one for each combination of shader defs and
+    /// entry point a shader is used with.
+    pub shader_modules: usize,
+}
+
+/// Shader defs describing the limitations of the target platform and the enabled cargo features.
+/// The [`PipelineCache`] adds them to every shader, along with [`auto_device_shader_defs`].
+///
+/// With the `webgl` feature on wasm, unless the `webgpu` feature is enabled:
+/// - `NO_ARRAY_TEXTURES_SUPPORT`
+/// - `NO_CUBE_ARRAY_TEXTURES_SUPPORT`
+/// - `SIXTEEN_BYTE_ALIGNMENT`
+///
+/// On the iOS simulator:
+/// - `NO_CUBE_ARRAY_TEXTURES_SUPPORT`
+///
+/// These are collected here rather than in [`ShaderCache::new()`], because the `webgl` and
+/// `webgpu` features they depend on are features of `bevy_render`, not of `bevy_shader`.
+pub fn platform_shader_defs() -> Vec<ShaderDefVal> {
+    let mut shader_defs = Vec::new();
+    #[cfg(all(feature = "webgl", target_arch = "wasm32", not(feature = "webgpu")))]
+    {
+        shader_defs.push("NO_ARRAY_TEXTURES_SUPPORT".into());
+        shader_defs.push("NO_CUBE_ARRAY_TEXTURES_SUPPORT".into());
+        shader_defs.push("SIXTEEN_BYTE_ALIGNMENT".into());
+    }
+
+    if cfg!(target_abi = "sim") {
+        shader_defs.push("NO_CUBE_ARRAY_TEXTURES_SUPPORT".into());
+    }
+
+    shader_defs
+}
+
+/// Shader defs describing the limits and features of `device`. The [`PipelineCache`] adds them
+/// to every shader, so shaders can always rely on them.
+///
+/// These defs are always set, to the value of the matching [`Limits`](wgpu::Limits) field:
+/// - `AVAILABLE_STORAGE_BUFFER_BINDINGS`: `max_storage_buffers_per_shader_stage`
+/// - `MAX_TEXTURE_DIMENSION_2D`: `max_texture_dimension_2d`
+/// - `MAX_TEXTURE_ARRAY_LAYERS`: `max_texture_array_layers`
+/// - `MAX_COMPUTE_INVOCATIONS_PER_WORKGROUP`: `max_compute_invocations_per_workgroup`
+/// - `MAX_COMPUTE_WORKGROUP_SIZE_X`, `MAX_COMPUTE_WORKGROUP_SIZE_Y` and
+///   `MAX_COMPUTE_WORKGROUP_SIZE_Z`: `max_compute_workgroup_size_x`, `_y` and `_z`
+/// - `MAX_COMPUTE_WORKGROUP_STORAGE_SIZE`: `max_compute_workgroup_storage_size`
+///
+/// These defs are only set if the device supports the matching [`Features`](wgpu::Features):
+/// - `SHADER_F16_SUPPORT`: `SHADER_F16`
+/// - `PUSH_CONSTANT_SUPPORT`: `PUSH_CONSTANTS`
+pub fn auto_device_shader_defs(device: &RenderDevice) -> Vec<ShaderDefVal> {
+    let limits = device.limits();
+    let mut shader_defs = [
+        (
+            "AVAILABLE_STORAGE_BUFFER_BINDINGS",
+            limits.max_storage_buffers_per_shader_stage,
+        ),
+        ("MAX_TEXTURE_DIMENSION_2D", limits.max_texture_dimension_2d),
+        ("MAX_TEXTURE_ARRAY_LAYERS", limits.max_texture_array_layers),
+        (
+            "MAX_COMPUTE_INVOCATIONS_PER_WORKGROUP",
+            limits.max_compute_invocations_per_workgroup,
+        ),
+        (
+            "MAX_COMPUTE_WORKGROUP_SIZE_X",
+            limits.max_compute_workgroup_size_x,
+        ),
+        (
+            "MAX_COMPUTE_WORKGROUP_SIZE_Y",
+            limits.max_compute_workgroup_size_y,
+        ),
+        (
+            "MAX_COMPUTE_WORKGROUP_SIZE_Z",
+            limits.max_compute_workgroup_size_z,
+        ),
+        (
+            "MAX_COMPUTE_WORKGROUP_STORAGE_SIZE",
+            limits.max_compute_workgroup_storage_size,
+        ),
+    ]
+    .into_iter()
+    .map(|(name, value)| ShaderDefVal::UInt(name.into(), value))
+    .collect::<Vec<_>>();
+
+    let features = device.features();
+    for (name, feature) in [
+        ("SHADER_F16_SUPPORT", wgpu::Features::SHADER_F16),
+        ("PUSH_CONSTANT_SUPPORT", wgpu::Features::PUSH_CONSTANTS),
+    ] {
+        if features.contains(feature) {
+            shader_defs.push(name.into());
         }
     }
+    shader_defs
 }
 
+type PipelineCreationResult = Result<(), PipelineCacheError>;
+
 type LayoutCacheKey = (Vec<BindGroupLayoutId>, Vec<PushConstantRange>);
+
+/// Deduplicates [`PipelineLayout`]s of pipelines that share identical bind group layouts and push
+/// constant ranges.
 #[derive(Default)]
-struct LayoutCache {
+pub(crate) struct LayoutCache {
     layouts: HashMap<LayoutCacheKey, Arc<WgpuWrapper<PipelineLayout>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
 }
 
 impl LayoutCache {
@@ -130,36 +652,63 @@ impl LayoutCache {
         render_device: &RenderDevice,
         bind_group_layouts: &[BindGroupLayout],
         push_constant_ranges: Vec<PushConstantRange>,
-    ) -> Arc<WgpuWrapper<PipelineLayout>> {
+    ) -> Result<Arc<WgpuWrapper<PipelineLayout>>, PipelineCacheError> {
         let bind_group_ids = bind_group_layouts.iter().map(BindGroupLayout::id).collect();
-        self.layouts
-            .entry((bind_group_ids, push_constant_ranges))
-            .or_insert_with_key(|(_, push_constant_ranges)| {
-                let bind_group_layouts = bind_group_layouts
-                    .iter()
-                    .map(BindGroupLayout::value)
-                    .collect::<Vec<_>>();
-                Arc::new(WgpuWrapper::new(render_device.create_pipeline_layout(
-                    &PipelineLayoutDescriptor {
-                        bind_group_layouts: &bind_group_layouts,
-                        push_constant_ranges,
-                        ..default()
-                    },
-                )))
-            })
-            .clone()
+        let key = (bind_group_ids, push_constant_ranges);
+        if let Some(layout) = self.layouts.get(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(layout.clone());
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        let bind_group_layouts = bind_group_layouts
+            .iter()
+            .map(BindGroupLayout::value)
+            .collect::<Vec<_>>();
+        render_device
+            .wgpu_device()
+            .push_error_scope(wgpu::ErrorFilter::Validation);
+        let layout = render_device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            bind_group_layouts: &bind_group_layouts,
+            push_constant_ranges: &key.1,
+            ..default()
+        });
+        let error = render_device.wgpu_device().pop_error_scope();
+        // Like for shader modules, the error is only caught here if it is reported right away,
+        // which is the case on native platforms.
+        if let Some(Some(wgpu::Error::Validation { description, .. })) =
+            bevy_tasks::futures::now_or_never(error)
+        {
+            return Err(PipelineCacheError::LayoutCreationFailed(description));
+        }
+
+        let layout = Arc::new(WgpuWrapper::new(layout));
+        self.layouts.insert(key, layout.clone());
+        Ok(layout)
+    }
+
+    /// The number of distinct pipeline layouts in the cache.
+    pub fn len(&self) -> usize {
+        self.layouts.len()
+    }
+
+    /// The number of lookups that reused an existing pipeline layout.
+    pub fn hit_count(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// The number of lookups that had to create a new pipeline layout.
+    pub fn miss_count(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
     }
 }
 
-#[expect(
-    clippy::result_large_err,
-    reason = "See https://github.com/bevyengine/bevy/issues/19220"
-)]
 fn load_module(
     render_device: &RenderDevice,
     shader_source: ShaderCacheSource,
     validate_shader: &ValidateShader,
-) -> Result<WgpuWrapper<ShaderModule>, PipelineCacheError> {
+    label: Option<&str>,
+) -> Result<WgpuWrapper<ShaderModule>, String> {
     let shader_source = match shader_source {
         #[cfg(feature = "shader_format_spirv")]
         ShaderCacheSource::SpirV(data) => wgpu::util::make_spirv(data),
@@ -172,7 +721,7 @@ fn load_module(
         ShaderCacheSource::Naga(src) => ShaderSource::Naga(Cow::Owned(src)),
     };
     let module_descriptor = ShaderModuleDescriptor {
-        label: None,
+        label,
         source: shader_source,
     };
 
@@ -201,7 +750,7 @@ fn load_module(
     if let Some(Some(wgpu::Error::Validation { description, .. })) =
         bevy_tasks::futures::now_or_never(error)
     {
-        return Err(PipelineCacheError::CreateShaderModule(description));
+        return Err(description);
     }
 
     Ok(shader_module)
@@ -237,22 +786,72 @@ impl BindGroupLayoutCache {
 /// graph starts being processed, as this requires access to the GPU.
 ///
 /// Note that the cache does not perform automatic deduplication of identical pipelines. It is
-/// up to the user not to insert the same pipeline twice to avoid wasting GPU resources.
+/// up to the user not to insert the same pipeline twice to avoid wasting GPU resources, or to
+/// opt into deduplication with [`PipelineCache::queue_render_pipeline_dedup()`].
 ///
 /// [`RenderSystems::Render`]: crate::RenderSystems::Render
 #[derive(Resource)]
 pub struct PipelineCache {
     layout_cache: Arc<Mutex<LayoutCache>>,
     bindgroup_layout_cache: Arc<Mutex<BindGroupLayoutCache>>,
-    shader_cache: Arc<Mutex<ShaderCache<WgpuWrapper<ShaderModule>, RenderDevice>>>,
+    shader_cache: Arc<RwLock<ShaderCache<WgpuWrapper<ShaderModule>, RenderDevice>>>,
     device: RenderDevice,
+    /// Driver-level cache of compiled pipeline binaries, if supported by the device.
+    wgpu_pipeline_cache: Option<Arc<WgpuWrapper<wgpu::PipelineCache>>>,
     pipelines: Vec<CachedPipeline>,
     waiting_pipelines: HashSet<CachedPipelineId>,
     new_pipelines: Mutex<Vec<CachedPipeline>>,
+    named_render_pipelines:
+        Mutex<HashMap<String, (CachedRenderPipelineId, RenderPipelineDescriptor)>>,
+    updated_pipelines: Mutex<Vec<(CachedPipelineId, PipelineDescriptor)>>,
+    /// Render pipelines queued with [`PipelineCache::queue_render_pipeline_dedup()`], by the hash
+    /// of their descriptor.
+    /// Descriptors are compared on a hash hit, so colliding hashes never share a pipeline.
+    deduplicated_render_pipelines:
+        Mutex<HashMap<u64, Vec<(RenderPipelineDescriptor, CachedRenderPipelineId)>>>,
+    /// Pipelines queued with [`PipelineCache::queue_render_pipeline_tagged()`] and
+    /// [`PipelineCache::queue_compute_pipeline_tagged()`], by the type and hash of their tag.
+    tagged_pipelines: Mutex<HashMap<(TypeId, u64), Vec<CachedPipelineId>>>,
+    /// Senders completing the futures returned by [`PipelineCache::queue_render_pipeline_async()`]
+    /// and [`PipelineCache::queue_compute_pipeline_async()`].
+    pipeline_waiters: Mutex<HashMap<CachedPipelineId, Vec<Sender<PipelineCreationResult>>>>,
+    /// Callbacks registered with [`PipelineCache::on_pipeline_ready()`] and
+    /// [`PipelineCache::on_compute_pipeline_ready()`].
+    ready_callbacks: Mutex<HashMap<CachedPipelineId, Vec<PipelineReadyCallback>>>,
     global_shader_defs: Vec<ShaderDefVal>,
+    shader_def_providers: Vec<fn(&World) -> Vec<ShaderDefVal>>,
+    /// Shader defs collected from the registered [`ShaderDefProvider`]s.
+    provided_shader_defs: Vec<ShaderDefVal>,
+    /// Whether the provided shader defs changed since the shaders were last extracted.
+    provided_shader_defs_changed: bool,
+    /// The shaders added with [`PipelineCache::add_inline_shader()`], without the global shader
+    /// defs, so they can be processed again when the provided shader defs change.
+    inline_shaders: HashMap<String, Shader>,
+    /// Timestamp and number of pipelines created for each of the last
+    /// [`COMPILATION_RATE_WINDOW`] frames.
+    compilation_history: VecDeque<(Instant, usize)>,
+    completed_this_frame: usize,
+    /// The number of processed pipelines in each state, updated as they are processed.
+    compilation_stats: PipelineCompilationStats,
+    /// Pipelines created since the last [`PipelineReady`] messages were written.
+    ready_pipelines: Vec<ReadyPipelineId>,
+    /// Compilation events since the last [`PipelineCompilationEvent`] messages were written.
+    compilation_events: Vec<PipelineCompilationEvent>,
+    /// Whether a pipeline was created since the start of the last queue processing system run.
+    has_newly_ready_pipelines: bool,
+    /// Total time spent in pipeline creation tasks, in microseconds.
+    compilation_time_us: Arc<AtomicU64>,
+    /// The current render frame, advanced once per frame by the system processing the queue, so
+    /// that extra calls to [`PipelineCache::process_queue()`] don't age pipelines.
+    frame: Arc<AtomicU64>,
     /// If `true`, disables asynchronous pipeline compilation.
     /// This has no effect on macOS, wasm, or without the `multi_threaded` feature.
     synchronous_pipeline_compilation: bool,
+    /// The maximum number of pipelines compiled at the same time, if limited, see
+    /// [`PipelineCache::new()`].
+    max_concurrent_compilations: Option<NonZeroUsize>,
+    /// The number of pipelines being compiled, counted while processing the queue.
+    compiling_pipelines: usize,
 }
 
 impl PipelineCache {
@@ -266,43 +865,307 @@ impl PipelineCache {
         self.waiting_pipelines.iter().copied()
     }
 
+    /// Whether pipelines are compiled synchronously, see
+    /// [`PipelineCache::set_synchronous_pipeline_compilation()`].
+    pub fn synchronous_pipeline_compilation(&self) -> bool {
+        self.synchronous_pipeline_compilation
+    }
+
+    /// Enables or disables synchronous pipeline compilation.
+    ///
+    /// This applies to pipelines whose creation starts from the next [`PipelineCache::process_queue()`]
+    /// on; pipelines already being created are not affected. This has no effect on macOS, wasm, or
+    /// without the `multi_threaded` feature.
+    ///
+    /// Pipelines queued with a [`CompilationHint`] other than [`CompilationHint::SystemDefault`]
+    /// ignore this setting.
+    ///
+    /// See also [`SynchronousPipelineCompilation`] to change this from the main world.
+    pub fn set_synchronous_pipeline_compilation(&mut self, sync: bool) {
+        self.synchronous_pipeline_compilation = sync;
+    }
+
+    /// Returns the IDs and descriptors of the pipelines whose GPU object is being created, including
+    /// pipelines being recreated after a change to their shaders.
+    ///
+    /// Unlike [`PipelineCache::waiting_pipelines()`], this doesn't include pipelines that are
+    /// queued but whose creation hasn't started yet.
+    pub fn iter_creating(&self) -> impl Iterator<Item = (CachedPipelineId, &PipelineDescriptor)> {
+        self.pipelines
+            .iter()
+            .enumerate()
+            .filter(|(_, pipeline)| {
+                matches!(
+                    pipeline.state,
+                    CachedPipelineState::Creating(_)
+                        | CachedPipelineState::HotReloading(Some(_), _)
+                )
+            })
+            .map(|(id, pipeline)| (id, &pipeline.descriptor))
+    }
+
+    /// Returns the number of pipelines whose GPU object is being created, see
+    /// [`PipelineCache::iter_creating()`].
+    pub fn creating_pipeline_count(&self) -> usize {
+        self.iter_creating().count()
+    }
+
+    /// Returns `true` if at least one pipeline was created during this frame's processing of the
+    /// queue.
+    ///
+    /// Render graph nodes that returned early because of a missing pipeline can use this as a
+    /// cheap check before querying the state of their pipelines again.
+    #[inline]
+    pub fn has_newly_ready_pipelines(&self) -> bool {
+        self.has_newly_ready_pipelines
+    }
+
+    /// Returns the IDs of all pipelines whose state matches `pred`, including pipelines that were
+    /// queued but not processed yet.
+    ///
+    /// Unlike [`PipelineCache::waiting_pipelines()`], this scans every pipeline in the cache and
+    /// can be called at any time, for example to display compilation progress.
+    pub fn pipeline_ids_with_state(
+        &self,
+        pred: impl Fn(&CachedPipelineState) -> bool,
+    ) -> Vec<CachedPipelineId> {
+        let new_pipelines = self
+            .new_pipelines
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        self.pipelines
+            .iter()
+            .chain(new_pipelines.iter())
+            .enumerate()
+            .filter(|(_, pipeline)| pred(&pipeline.state))
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// Returns the IDs of all pipelines that are queued for creation or being created.
+    pub fn all_waiting_pipeline_ids(&self) -> Vec<CachedPipelineId> {
+        self.pipeline_ids_with_state(CachedPipelineState::is_pending)
+    }
+
+    /// Returns the IDs of all pipelines that failed to be created.
+    pub fn all_failed_pipeline_ids(&self) -> Vec<CachedPipelineId> {
+        self.pipeline_ids_with_state(CachedPipelineState::is_failed)
+    }
+
+    /// Returns the IDs of all pipelines that were created successfully.
+    pub fn all_ready_pipeline_ids(&self) -> Vec<CachedPipelineId> {
+        self.pipeline_ids_with_state(CachedPipelineState::is_ready)
+    }
+
     /// Create a new pipeline cache associated with the given render device.
+    ///
+    /// `pipeline_cache_data` seeds the driver-level pipeline cache with a blob previously returned
+    /// by [`PipelineCache::pipeline_cache_data()`], so that pipelines compiled in an earlier
+    /// session don't have to be compiled again. Data that was created by another adapter or driver
+    /// version is discarded, and the cache starts out empty.
+    ///
+    /// `max_concurrent_compilations` limits the number of pipelines compiled at the same time.
+    /// Pipelines queued beyond this limit wait until earlier compilations finish, which avoids
+    /// flooding the task pool and spiking memory usage when hundreds of pipelines are queued at
+    /// once, for example at startup. `None` doesn't limit compilations.
     pub fn new(
         device: RenderDevice,
         render_adapter: RenderAdapter,
         synchronous_pipeline_compilation: bool,
+        pipeline_cache_data: Option<Vec<u8>>,
+        max_concurrent_compilations: Option<NonZeroUsize>,
     ) -> Self {
-        let mut global_shader_defs = Vec::new();
-        #[cfg(all(feature = "webgl", target_arch = "wasm32", not(feature = "webgpu")))]
-        {
-            global_shader_defs.push("NO_ARRAY_TEXTURES_SUPPORT".into());
-            global_shader_defs.push("NO_CUBE_ARRAY_TEXTURES_SUPPORT".into());
-            global_shader_defs.push("SIXTEEN_BYTE_ALIGNMENT".into());
-        }
-
-        if cfg!(target_abi = "sim") {
-            global_shader_defs.push("NO_CUBE_ARRAY_TEXTURES_SUPPORT".into());
-        }
-
-        global_shader_defs.push(ShaderDefVal::UInt(
-            String::from("AVAILABLE_STORAGE_BUFFER_BINDINGS"),
-            device.limits().max_storage_buffers_per_shader_stage,
-        ));
+        let mut global_shader_defs = platform_shader_defs();
+        global_shader_defs.extend(auto_device_shader_defs(&device));
+
+        let wgpu_pipeline_cache = device
+            .features()
+            .contains(wgpu::Features::PIPELINE_CACHE)
+            .then(|| {
+                // SAFETY: the data was returned by `PipelineCache::get_data`, as documented on
+                // `PipelineCache::new`. wgpu checks that it was created for this adapter and, with
+                // `fallback` set, creates an empty cache instead of failing if it wasn't.
+                let cache = unsafe {
+                    device
+                        .wgpu_device()
+                        .create_pipeline_cache(&wgpu::PipelineCacheDescriptor {
+                            label: Some("bevy_pipeline_cache"),
+                            data: pipeline_cache_data.as_deref(),
+                            fallback: true,
+                        })
+                };
+                Arc::new(WgpuWrapper::new(cache))
+            });
 
         Self {
-            shader_cache: Arc::new(Mutex::new(ShaderCache::new(
+            shader_cache: Arc::new(RwLock::new(ShaderCache::new(
                 device.features(),
                 render_adapter.get_downlevel_capabilities().flags,
                 load_module,
             ))),
             device,
+            wgpu_pipeline_cache,
             layout_cache: default(),
             bindgroup_layout_cache: default(),
             waiting_pipelines: default(),
             new_pipelines: default(),
+            named_render_pipelines: default(),
+            updated_pipelines: default(),
+            deduplicated_render_pipelines: default(),
+            tagged_pipelines: default(),
+            pipeline_waiters: default(),
+            ready_callbacks: default(),
             pipelines: default(),
             global_shader_defs,
+            shader_def_providers: Vec::new(),
+            provided_shader_defs: Vec::new(),
+            provided_shader_defs_changed: false,
+            inline_shaders: default(),
+            compilation_history: VecDeque::with_capacity(COMPILATION_RATE_WINDOW),
+            completed_this_frame: 0,
+            compilation_stats: default(),
+            ready_pipelines: Vec::new(),
+            compilation_events: Vec::new(),
+            has_newly_ready_pipelines: false,
+            compilation_time_us: default(),
+            frame: default(),
             synchronous_pipeline_compilation,
+            max_concurrent_compilations,
+            compiling_pipelines: 0,
+        }
+    }
+
+    /// The number of pipelines that were being compiled after the queue was last processed.
+    pub fn compiling_pipeline_count(&self) -> usize {
+        self.compiling_pipelines
+    }
+
+    fn compilation_limit_reached(&self) -> bool {
+        self.max_concurrent_compilations
+            .is_some_and(|max| self.compiling_pipelines >= max.get())
+    }
+
+    /// Set how conflicting values for the same shader def are resolved when processing shaders.
+    ///
+    /// Defaults to [`ShaderDefConflictPolicy::WarnUseLast`].
+    #[must_use]
+    pub fn with_shader_def_conflict_policy(self, policy: ShaderDefConflictPolicy) -> Self {
+        self.shader_cache.write().shader_def_conflict_policy = policy;
+        self
+    }
+
+    /// Register a render world [`Resource`] whose [`ShaderDefProvider::shader_defs()`] are added to
+    /// the global shader defs.
+    ///
+    /// Providers are evaluated every frame during the [`ExtractSchedule`], and their defs are
+    /// merged into shaders as they are processed. When the defs of a provider change, all shaders
+    /// are processed again and the pipelines using them are queued for creation again.
+    ///
+    /// [`ExtractSchedule`]: crate::ExtractSchedule
+    pub fn register_shader_def_provider<R: Resource + ShaderDefProvider>(&mut self) {
+        self.shader_def_providers.push(|world| {
+            world
+                .get_resource::<R>()
+                .map(R::shader_defs)
+                .unwrap_or_default()
+        });
+    }
+
+    /// Whether composed [`naga::Module`]s are retained after shader processing.
+    ///
+    /// See [`PipelineCache::set_retain_naga_modules()`].
+    pub fn retain_naga_modules(&self) -> bool {
+        self.shader_cache.read().retain_naga_modules
+    }
+
+    /// Enable or disable retaining the composed [`naga::Module`] of every processed shader, so it
+    /// can be inspected with [`PipelineCache::get_naga_module()`].
+    ///
+    /// This is disabled by default, as it roughly doubles the memory used by processed shaders.
+    /// Only shaders processed after this is enabled will have their module retained.
+    pub fn set_retain_naga_modules(&mut self, retain_naga_modules: bool) {
+        self.shader_cache.write().retain_naga_modules = retain_naga_modules;
+    }
+
+    /// Get the composed [`naga::Module`] of a shader processed with the given shader defs.
+    ///
+    /// This is useful for custom analysis such as binding layout validation or resource usage
+    /// tracking. Returns `None` unless [`PipelineCache::set_retain_naga_modules()`] was enabled
+    /// when the shader was processed.
+    pub fn get_naga_module(
+        &self,
+        shader_id: AssetId<Shader>,
+        shader_defs: &[ShaderDefVal],
+    ) -> Option<Arc<naga::Module>> {
+        self.shader_cache
+            .read()
+            .get_naga_module(shader_id, shader_defs)
+    }
+
+    /// Compose a shader with the given shader defs and return the final module as WGSL.
+    ///
+    /// This is meant for debugging shaders that fail validation or produce incorrect output, and
+    /// isn't optimized for speed. Global shader defs are already part of every extracted shader,
+    /// so only the shader defs of the pipeline descriptor need to be passed.
+    pub fn export_naga_module(
+        &mut self,
+        id: AssetId<Shader>,
+        shader_defs: &[ShaderDefVal],
+    ) -> Result<String, PipelineCacheError> {
+        self.shader_cache
+            .write()
+            .export_naga_module(id, shader_defs)
+    }
+
+    /// The number of pipelines created per second, averaged over the last 60 frames.
+    ///
+    /// Returns `0.0` until at least two frames worth of pipelines were processed.
+    pub fn compilation_rate(&self) -> f32 {
+        let (Some((first, _)), Some((last, _))) = (
+            self.compilation_history.front(),
+            self.compilation_history.back(),
+        ) else {
+            return 0.0;
+        };
+        let elapsed = last.duration_since(*first).as_secs_f32();
+        if elapsed <= 0.0 {
+            return 0.0;
+        }
+
+        // Pipelines of the oldest frame were created before its timestamp.
+        let completed: usize = self
+            .compilation_history
+            .iter()
+            .skip(1)
+            .map(|(_, completed)| completed)
+            .sum();
+        completed as f32 / elapsed
+    }
+
+    /// Get the serialized contents of the driver-level pipeline cache.
+    ///
+    /// Applications can write this blob to disk and pass it back through
+    /// [`RenderPlugin::pipeline_cache_data`](crate::RenderPlugin::pipeline_cache_data) on the next
+    /// start, to let drivers reuse compiled pipeline binaries across sessions. Returns `None` if the device doesn't support
+    /// [`PIPELINE_CACHE`](wgpu::Features::PIPELINE_CACHE) or if the backend has no data to
+    /// provide.
+    pub fn pipeline_cache_data(&self) -> Option<Vec<u8>> {
+        self.wgpu_pipeline_cache.as_ref()?.get_data()
+    }
+
+    /// Get a snapshot of the number of pipelines in each state, and of the total time spent
+    /// creating them.
+    pub fn stats(&self) -> PipelineCompilationStats {
+        let unprocessed = self
+            .new_pipelines
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len();
+        PipelineCompilationStats {
+            queued: self.compilation_stats.queued + unprocessed,
+            total_compilation_time_ms: self.compilation_time_us.load(Ordering::Relaxed) as f64
+                / 1000.0,
+            ..self.compilation_stats
         }
     }
 
@@ -362,37 +1225,520 @@ impl PipelineCache {
         }
     }
 
-    /// Try to retrieve a render pipeline GPU object from a cached ID.
+    /// Get the IDs of all render pipelines using the given shader.
     ///
-    /// # Returns
+    /// Only pipelines that were processed by the cache at least once are known to use a shader.
+    pub fn find_render_pipelines_for_shader(
+        &self,
+        shader_id: AssetId<Shader>,
+    ) -> Vec<CachedRenderPipelineId> {
+        self.find_pipelines_for_shader(shader_id)
+            .into_iter()
+            .filter_map(|id| match self.pipelines.get(id)?.descriptor {
+                PipelineDescriptor::RenderPipelineDescriptor(_) => Some(CachedRenderPipelineId(id)),
+                PipelineDescriptor::ComputePipelineDescriptor(_) => None,
+            })
+            .collect()
+    }
+
+    /// Get the IDs of all compute pipelines using the given shader.
+    ///
+    /// Only pipelines that were processed by the cache at least once are known to use a shader.
+    pub fn find_compute_pipelines_for_shader(
+        &self,
+        shader_id: AssetId<Shader>,
+    ) -> Vec<CachedComputePipelineId> {
+        self.find_pipelines_for_shader(shader_id)
+            .into_iter()
+            .filter_map(|id| match self.pipelines.get(id)?.descriptor {
+                PipelineDescriptor::RenderPipelineDescriptor(_) => None,
+                PipelineDescriptor::ComputePipelineDescriptor(_) => {
+                    Some(CachedComputePipelineId(id))
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the number of shaders and of shader modules created from them.
+    ///
+    /// A large number of modules per shader points at many combinations of shader defs, each of
+    /// which has to be compiled and kept in memory separately.
+    pub fn shader_stats(&self) -> ShaderCacheStats {
+        let shader_cache = self.shader_cache.read();
+        ShaderCacheStats {
+            unique_shaders: shader_cache.unique_shader_count(),
+            shader_modules: shader_cache.shader_module_count(),
+        }
+    }
+
+    fn find_pipelines_for_shader(&self, shader_id: AssetId<Shader>) -> Vec<CachedPipelineId> {
+        self.shader_cache.read().pipelines_using_shader(shader_id)
+    }
+
+    /// Get all shaders that import the given shader, directly or transitively.
+    ///
+    /// These are the shaders that are reprocessed, along with the pipelines using them, when the
+    /// given shader changes. Together with [`PipelineCache::get_shader_imports`], this can be
+    /// used to inspect the shader dependency graph.
+    pub fn get_shader_dependents(&self, shader_id: AssetId<Shader>) -> Vec<AssetId<Shader>> {
+        self.shader_cache.read().get_shader_dependents(shader_id)
+    }
+
+    /// Get the shaders directly imported by the given shader.
+    ///
+    /// Imports are only known once they were resolved, i.e. once the imported shaders are loaded.
+    pub fn get_shader_imports(&self, shader_id: AssetId<Shader>) -> Vec<AssetId<Shader>> {
+        self.shader_cache.read().get_shader_imports(shader_id)
+    }
+
+    /// Composes the shader `id` with `shader_defs` and validates the result with naga, without
+    /// creating a GPU pipeline or shader module.
+    ///
+    /// This is a dry run of shader processing, for example to check in CI that the shaders of a
+    /// plugin compile with the shader defs it uses. The global shader defs of the cache apply as
+    /// usual. To validate shaders without a [`RenderDevice`], use [`ShaderCache::validate()`]
+    /// directly.
+    pub fn validate_shader_compilation(
+        &self,
+        id: AssetId<Shader>,
+        shader_defs: &[ShaderDefVal],
+    ) -> Result<(), PipelineCacheError> {
+        self.shader_cache.write().validate(id, shader_defs)
+    }
+
+    /// Get the workgroup size of a compute pipeline, as declared by the `@workgroup_size`
+    /// attribute of its entry point, for example to compute the number of workgroups to dispatch.
+    ///
+    /// Returns `None` until the shader of the pipeline was processed, and for shaders not
+    /// composed by `naga_oil`, such as SPIR-V shaders.
+    pub fn get_compute_workgroup_size(&self, id: CachedComputePipelineId) -> Option<[u32; 3]> {
+        let PipelineDescriptor::ComputePipelineDescriptor(descriptor) =
+            &self.pipelines.get(id.0)?.descriptor
+        else {
+            return None;
+        };
+        self.shader_cache.read().get_workgroup_size(
+            descriptor.shader.id(),
+            &descriptor.shader_defs,
+            descriptor.entry_point.as_deref(),
+        )
+    }
+
+    /// Get the import path of the given shader, as used in error messages and by other shaders to
+    /// import it, or `None` if the shader isn't loaded.
+    pub fn get_import_path_for_shader(&self, shader_id: AssetId<Shader>) -> Option<ShaderImport> {
+        self.shader_cache
+            .read()
+            .get_import_path_for_shader(shader_id)
+            .cloned()
+    }
+
+    /// Try to retrieve a render pipeline GPU object from a cached ID.
+    ///
+    /// # Returns
     ///
     /// This method returns a successfully created render pipeline if any, or `None` if the pipeline
     /// was not created yet or if there was an error during creation. You can check the actual creation
     /// state with [`PipelineCache::get_render_pipeline_state()`].
     #[inline]
     pub fn get_render_pipeline(&self, id: CachedRenderPipelineId) -> Option<&RenderPipeline> {
-        if let CachedPipelineState::Ok(Pipeline::RenderPipeline(pipeline)) =
-            &self.pipelines.get(id.0)?.state
-        {
+        let cached_pipeline = self.pipelines.get(id.0)?;
+        if let Some(Pipeline::RenderPipeline(pipeline)) = cached_pipeline.state.as_ok() {
+            cached_pipeline
+                .last_accessed_frame
+                .store(self.frame.load(Ordering::Relaxed), Ordering::Relaxed);
             Some(pipeline)
         } else {
             None
         }
     }
 
+    /// Number of frames since a render pipeline was last retrieved with
+    /// [`PipelineCache::get_render_pipeline()`], or since it was queued if it never was.
+    ///
+    /// Pipelines that haven't been used for many frames are candidates for eviction.
+    pub fn pipeline_age(&self, id: CachedRenderPipelineId) -> u64 {
+        self.pipelines.get(id.0).map_or(0, |pipeline| {
+            self.frame
+                .load(Ordering::Relaxed)
+                .saturating_sub(pipeline.last_accessed_frame.load(Ordering::Relaxed))
+        })
+    }
+
     /// Wait for a render pipeline to finish compiling.
     #[inline]
     pub fn block_on_render_pipeline(&mut self, id: CachedRenderPipelineId) {
-        if self.pipelines.len() <= id.0 {
+        self.block_on_pipeline(id.0);
+    }
+
+    /// Wait for a compute pipeline to finish compiling.
+    #[inline]
+    pub fn block_on_compute_pipeline(&mut self, id: CachedComputePipelineId) {
+        self.block_on_pipeline(id.0);
+    }
+
+    /// Process the queue and wait for every pipeline being created to finish compiling, including
+    /// pipelines queued while waiting.
+    ///
+    /// This blocks the calling thread, so it should only be used where stalls don't matter, such
+    /// as headless tests or automated screenshot capture. Pipelines waiting on a shader that isn't
+    /// loaded yet can't make progress while the thread is blocked, and are left pending.
+    pub fn wait_for_all_pipelines(&mut self) {
+        loop {
             self.process_queue();
+            let creating = self
+                .waiting_pipelines
+                .iter()
+                .copied()
+                .filter(|&id| {
+                    matches!(
+                        self.pipelines[id].state,
+                        CachedPipelineState::Creating(_)
+                            | CachedPipelineState::HotReloading(Some(_), _)
+                    )
+                })
+                .collect::<Vec<_>>();
+            let has_new_pipelines = !self
+                .new_pipelines
+                .get_mut()
+                .unwrap_or_else(PoisonError::into_inner)
+                .is_empty();
+            if creating.is_empty() && !has_new_pipelines {
+                break;
+            }
+            for id in creating {
+                self.block_on_pipeline(id);
+            }
         }
+    }
 
-        let state = &mut self.pipelines[id.0].state;
-        if let CachedPipelineState::Creating(task) = state {
-            *state = match bevy_tasks::block_on(task) {
-                Ok(p) => CachedPipelineState::Ok(p),
-                Err(e) => CachedPipelineState::Err(e),
-            };
+    /// Insert a render pipeline into the cache, and block until it was created.
+    ///
+    /// This is meant for one-shot work that needs a pipeline right away without going through the
+    /// queue over several frames, such as tests or baking tools in an editor. It stalls the
+    /// calling thread while the pipeline compiles, so it must not be used for real-time rendering.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the pipeline creation. As the thread is blocked, a shader that isn't
+    /// loaded yet can't become available, so [`PipelineCacheError::ShaderNotLoaded`] and
+    /// [`PipelineCacheError::ShaderImportNotYetAvailable`] are returned as well; the pipeline
+    /// stays queued and is retried as usual.
+    pub fn get_render_pipeline_blocking(
+        &mut self,
+        descriptor: RenderPipelineDescriptor,
+    ) -> Result<CachedRenderPipelineId, PipelineCacheError> {
+        let id = self.queue_render_pipeline(descriptor);
+        self.create_pipeline_blocking(id.0).map(|()| id)
+    }
+
+    /// Insert a compute pipeline into the cache, and block until it was created.
+    ///
+    /// See [`PipelineCache::get_render_pipeline_blocking()`].
+    pub fn get_compute_pipeline_blocking(
+        &mut self,
+        descriptor: ComputePipelineDescriptor,
+    ) -> Result<CachedComputePipelineId, PipelineCacheError> {
+        let id = self.queue_compute_pipeline(descriptor);
+        self.create_pipeline_blocking(id.0).map(|()| id)
+    }
+
+    fn create_pipeline_blocking(&mut self, id: CachedPipelineId) -> PipelineCreationResult {
+        let mut created = core::pin::pin!(self.wait_for_pipeline(id));
+        loop {
+            self.process_queue();
+            self.block_on_pipeline(id);
+            if let Some(result) = bevy_tasks::futures::now_or_never(created.as_mut()) {
+                return result;
+            }
+            // Errors that are retried don't complete the future.
+            match &self.pipelines[id].state {
+                CachedPipelineState::Err(PipelineCacheError::ShaderNotLoaded(shader, import)) => {
+                    return Err(PipelineCacheError::ShaderNotLoaded(*shader, import.clone()));
+                }
+                CachedPipelineState::Err(PipelineCacheError::ShaderImportNotYetAvailable) => {
+                    return Err(PipelineCacheError::ShaderImportNotYetAvailable);
+                }
+                // Still queued behind other compilations, or the error is reported to the
+                // future once processed.
+                _ => {}
+            }
+        }
+    }
+
+    fn block_on_pipeline(&mut self, id: CachedPipelineId) {
+        if self.pipelines.len() <= id {
+            self.process_queue();
+        }
+
+        let mut pipelines = mem::take(&mut self.pipelines);
+        let cached_pipeline = &mut pipelines[id];
+        match &mut cached_pipeline.state {
+            CachedPipelineState::Creating(task) => {
+                let result = bevy_tasks::block_on(&mut **task);
+                self.finish_compilation(cached_pipeline, id, result);
+            }
+            CachedPipelineState::HotReloading(Some(task), _) => {
+                let result = bevy_tasks::block_on(&mut **task);
+                self.finish_hot_reload(cached_pipeline, id, result);
+            }
+            _ => {}
+        }
+        self.pipelines = pipelines;
+    }
+
+    /// Remove a render pipeline from the cache, dropping its GPU object.
+    ///
+    /// The ID stays valid, but the pipeline is left in the [`CachedPipelineState::Evicted`] state
+    /// and [`PipelineCache::get_render_pipeline()`] returns `None` for it from then on. IDs that
+    /// weren't returned by this cache, like [`CachedRenderPipelineId::INVALID`], are ignored.
+    pub fn remove_render_pipeline(&mut self, id: CachedRenderPipelineId) {
+        self.remove_pipeline(id.0);
+    }
+
+    /// Remove a compute pipeline from the cache, dropping its GPU object.
+    ///
+    /// The ID stays valid, but the pipeline is left in the [`CachedPipelineState::Evicted`] state
+    /// and [`PipelineCache::get_compute_pipeline()`] returns `None` for it from then on. IDs that
+    /// weren't returned by this cache, like [`CachedComputePipelineId::INVALID`], are ignored.
+    pub fn remove_compute_pipeline(&mut self, id: CachedComputePipelineId) {
+        self.remove_pipeline(id.0);
+    }
+
+    fn remove_pipeline(&mut self, id: CachedPipelineId) {
+        let new_pipelines = self
+            .new_pipelines
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        let cached_pipeline = match id.checked_sub(self.pipelines.len()) {
+            // The pipeline was queued but not processed yet.
+            Some(index) => new_pipelines.get_mut(index),
+            None => self.pipelines.get_mut(id),
+        };
+        // IDs that were never queued, like `CachedRenderPipelineId::INVALID`, are ignored.
+        let Some(cached_pipeline) = cached_pipeline else {
+            return;
+        };
+        cached_pipeline.state = CachedPipelineState::Evicted;
+        // Pipelines that weren't processed yet are counted once they are.
+        if let Some(cached_pipeline) = self.pipelines.get_mut(id) {
+            cached_pipeline.update_stats(&mut self.compilation_stats);
+        }
+        self.waiting_pipelines.remove(&id);
+
+        // Dropping the senders leaves the futures waiting on this pipeline pending forever.
+        self.pipeline_waiters
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&id);
+        self.ready_callbacks
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&id);
+        self.updated_pipelines
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|(updated_id, _)| *updated_id != id);
+
+        // Queueing the same descriptor or name again creates a new pipeline instead of returning
+        // the removed one.
+        self.named_render_pipelines
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|_, (named_id, _)| named_id.0 != id);
+        self.deduplicated_render_pipelines
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|_, candidates| {
+                candidates.retain(|(_, candidate_id)| candidate_id.0 != id);
+                !candidates.is_empty()
+            });
+        self.tagged_pipelines
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|_, ids| {
+                ids.retain(|&tagged_id| tagged_id != id);
+                !ids.is_empty()
+            });
+    }
+
+    fn compilation_event(&mut self, id: CachedPipelineId, phase: CompilationPhase) {
+        self.compilation_events.push(PipelineCompilationEvent {
+            id,
+            phase,
+            timestamp: Instant::now(),
+        });
+    }
+
+    /// Ends the creation of the GPU object of a pipeline that isn't hot reloading, whether it was
+    /// created synchronously, polled from its task, or blocked on. Returns `true` if the pipeline
+    /// was created.
+    ///
+    /// This emits the [`CompilationPhase::Completed`] or [`CompilationPhase::Failed`] event
+    /// matching the [`CompilationPhase::Started`] one emitted when the creation started.
+    fn finish_compilation(
+        &mut self,
+        cached_pipeline: &mut CachedPipeline,
+        id: CachedPipelineId,
+        result: Result<Pipeline, PipelineCacheError>,
+    ) -> bool {
+        cached_pipeline.compilation_finished();
+        match result {
+            Ok(pipeline) => {
+                self.compilation_event(id, CompilationPhase::Completed);
+                cached_pipeline.state = CachedPipelineState::Ok(pipeline);
+                self.pipeline_created(ReadyPipelineId::new(id, &cached_pipeline.descriptor));
+                true
+            }
+            Err(err) => {
+                self.compilation_event(id, CompilationPhase::Failed);
+                self.pipeline_failed(id, &cached_pipeline.descriptor, &err);
+                cached_pipeline.state = CachedPipelineState::Err(err);
+                false
+            }
+        }
+    }
+
+    /// Logs the error of a pipeline and completes the futures waiting on it, unless the error is
+    /// retried.
+    fn pipeline_failed(
+        &mut self,
+        id: CachedPipelineId,
+        descriptor: &PipelineDescriptor,
+        err: &PipelineCacheError,
+    ) {
+        match err {
+            // Retried, see `process_pipeline`.
+            PipelineCacheError::ShaderNotLoaded(..)
+            | PipelineCacheError::ShaderImportNotYetAvailable => return,
+
+            // Shader could not be processed ... retrying won't help
+            PipelineCacheError::ProcessShaderError(error_detail) => {
+                if std::env::var("VERBOSE_SHADER_ERROR")
+                    .is_ok_and(|v| !(v.is_empty() || v == "0" || v == "false"))
+                {
+                    error!("{}", pipeline_error_context(descriptor));
+                }
+                error!("failed to process shader error:\n{}", error_detail);
+            }
+            PipelineCacheError::CreateShaderModule(module_error) => {
+                error!("failed to create shader module: {}", module_error);
+            }
+            PipelineCacheError::LayoutCreationFailed(description) => {
+                error!(
+                    "failed to create pipeline layout: {}\n{}",
+                    description,
+                    pipeline_error_context(descriptor)
+                );
+            }
+            PipelineCacheError::NagaValidationFailed(validation_error) => {
+                error!(
+                    "shader validation failed: {}\n{}",
+                    validation_error,
+                    pipeline_error_context(descriptor)
+                );
+            }
+            PipelineCacheError::ShaderCycleDetected(_) => {
+                error!("{}\n{}", err, pipeline_error_context(descriptor));
+            }
+        }
+        self.notify_pipeline_waiters(id, || Err(err.clone()));
+    }
+
+    fn pipeline_created(&mut self, id: ReadyPipelineId) {
+        self.completed_this_frame += 1;
+        self.ready_pipelines.push(id);
+        self.has_newly_ready_pipelines = true;
+        let index = match id {
+            ReadyPipelineId::Render(id) => id.id(),
+            ReadyPipelineId::Compute(id) => id.id(),
+        };
+        self.notify_pipeline_waiters(index, || Ok(()));
+        let callbacks = self
+            .ready_callbacks
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&index);
+        for callback in callbacks.into_iter().flatten() {
+            callback();
+        }
+    }
+
+    fn notify_pipeline_waiters(
+        &mut self,
+        id: CachedPipelineId,
+        result: impl Fn() -> PipelineCreationResult,
+    ) {
+        let waiters = self
+            .pipeline_waiters
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        for sender in waiters.remove(&id).into_iter().flatten() {
+            // The future may have been dropped, in which case nobody is waiting anymore.
+            let _ = sender.try_send(result());
+        }
+    }
+
+    /// Registers a callback to run once, when the render pipeline `id` was created.
+    ///
+    /// The callback runs while the cache processes its queue, in the render world. If the
+    /// pipeline was already created, the callback runs immediately. Callbacks of a pipeline
+    /// removed from the cache before being created are dropped without running.
+    pub fn on_pipeline_ready(
+        &self,
+        id: CachedRenderPipelineId,
+        callback: impl FnOnce() + Send + 'static,
+    ) {
+        self.on_ready(id.0, Box::new(callback));
+    }
+
+    /// Registers a callback to run once, when the compute pipeline `id` was created.
+    ///
+    /// See [`PipelineCache::on_pipeline_ready()`] for details.
+    pub fn on_compute_pipeline_ready(
+        &self,
+        id: CachedComputePipelineId,
+        callback: impl FnOnce() + Send + 'static,
+    ) {
+        self.on_ready(id.0, Box::new(callback));
+    }
+
+    fn on_ready(&self, id: CachedPipelineId, callback: PipelineReadyCallback) {
+        let created = self.pipelines.get(id).is_some_and(|pipeline| {
+            matches!(
+                pipeline.state,
+                CachedPipelineState::Ok(_) | CachedPipelineState::HotReloading(..)
+            )
+        });
+        if created {
+            callback();
+            return;
+        }
+        self.ready_callbacks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(id)
+            .or_default()
+            .push(callback);
+    }
+
+    fn wait_for_pipeline(
+        &self,
+        id: CachedPipelineId,
+    ) -> impl Future<Output = PipelineCreationResult> + use<> {
+        let (sender, receiver) = async_channel::bounded(1);
+        self.pipeline_waiters
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(id)
+            .or_default()
+            .push(sender);
+        async move {
+            match receiver.recv().await {
+                Ok(result) => result,
+                // The pipeline was removed from the cache, or the cache itself was dropped.
+                Err(_) => core::future::pending().await,
+            }
         }
     }
 
@@ -405,70 +1751,365 @@ impl PipelineCache {
     /// state with [`PipelineCache::get_compute_pipeline_state()`].
     #[inline]
     pub fn get_compute_pipeline(&self, id: CachedComputePipelineId) -> Option<&ComputePipeline> {
-        if let CachedPipelineState::Ok(Pipeline::ComputePipeline(pipeline)) =
-            &self.pipelines.get(id.0)?.state
-        {
+        if let Some(Pipeline::ComputePipeline(pipeline)) = self.pipelines.get(id.0)?.state.as_ok() {
             Some(pipeline)
         } else {
             None
         }
     }
 
-    /// Insert a render pipeline into the cache, and queue its creation.
+    /// Check that no cached pipeline failed to be created.
+    ///
+    /// # Returns
+    ///
+    /// This method returns the errors of all pipelines in the [`CachedPipelineState::Err`] state,
+    /// if any. Pipelines that are still queued or being created are not considered failed, nor are
+    /// pipelines waiting on a shader or import that isn't loaded yet, as those errors are retried.
+    /// This is mostly useful in tests, after pipelines were created with
+    /// [`PipelineCache::block_on_render_pipeline()`] or repeated calls to
+    /// [`PipelineCache::process_queue()`].
+    pub fn verify_all_pipelines_ok(&self) -> Result<(), Vec<PipelineCacheError>> {
+        let errors = self
+            .pipelines
+            .iter()
+            .filter_map(|pipeline| match &pipeline.state {
+                CachedPipelineState::Err(err)
+                    if !matches!(
+                        err,
+                        PipelineCacheError::ShaderNotLoaded(..)
+                            | PipelineCacheError::ShaderImportNotYetAvailable
+                    ) =>
+                {
+                    Some(err.clone())
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Queue every pipeline in the [`CachedPipelineState::Err`] state for creation again.
+    ///
+    /// Pipelines that failed with an error that isn't retried automatically, such as a shader
+    /// that couldn't be processed, otherwise stay failed. This gives hot-reload tools a way to
+    /// recover after a shader was fixed, without restarting the application.
+    pub fn clear_all_errors(&mut self) {
+        for (id, cached_pipeline) in self.pipelines.iter_mut().enumerate() {
+            if cached_pipeline.state.is_failed() {
+                cached_pipeline.state = CachedPipelineState::Queued;
+                self.waiting_pipelines.insert(id);
+            }
+        }
+    }
+
+    /// Insert a render pipeline into the cache, and queue its creation.
+    ///
+    /// The pipeline is always inserted and queued for creation. There is no attempt to deduplicate it with
+    /// an already cached pipeline.
+    ///
+    /// # Returns
+    ///
+    /// This method returns the unique render shader ID of the cached pipeline, which can be used to query
+    /// the caching state with [`get_render_pipeline_state()`] and to retrieve the created GPU pipeline once
+    /// it's ready with [`get_render_pipeline()`].
+    ///
+    /// [`get_render_pipeline_state()`]: PipelineCache::get_render_pipeline_state
+    /// [`get_render_pipeline()`]: PipelineCache::get_render_pipeline
+    pub fn queue_render_pipeline(
+        &self,
+        descriptor: RenderPipelineDescriptor,
+    ) -> CachedRenderPipelineId {
+        self.queue_render_pipeline_with_hint(descriptor, CompilationHint::SystemDefault)
+    }
+
+    /// Insert a render pipeline into the cache, and queue its creation with the given
+    /// [`CompilationHint`].
+    ///
+    /// The hint overrides the [`PipelineCache::synchronous_pipeline_compilation()`] setting for
+    /// this pipeline, for example to create the pipelines needed on startup synchronously while
+    /// compiling the others in the background. See [`PipelineCache::queue_render_pipeline()`].
+    pub fn queue_render_pipeline_with_hint(
+        &self,
+        descriptor: RenderPipelineDescriptor,
+        hint: CompilationHint,
+    ) -> CachedRenderPipelineId {
+        CachedRenderPipelineId(self.queue_pipeline_with_hint(
+            PipelineDescriptor::RenderPipelineDescriptor(Box::new(descriptor)),
+            hint,
+        ))
+    }
+
+    fn queue_pipeline_with_hint(
+        &self,
+        descriptor: PipelineDescriptor,
+        hint: CompilationHint,
+    ) -> CachedPipelineId {
+        let mut new_pipelines = self
+            .new_pipelines
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let id = self.pipelines.len() + new_pipelines.len();
+        let mut pipeline = CachedPipeline::new(descriptor, self.frame.load(Ordering::Relaxed));
+        pipeline.compilation_hint = hint;
+        new_pipelines.push(pipeline);
+        id
+    }
+
+    /// Insert several render pipelines into the cache, and queue their creation.
+    ///
+    /// This behaves like calling [`PipelineCache::queue_render_pipeline()`] for each descriptor,
+    /// but only locks the queue once, reducing contention when many pipelines are queued at once.
+    pub fn queue_render_pipelines_bulk(
+        &self,
+        descriptors: impl IntoIterator<Item = RenderPipelineDescriptor>,
+    ) -> Vec<CachedRenderPipelineId> {
+        self.queue_pipelines_bulk(
+            descriptors.into_iter().map(|descriptor| {
+                PipelineDescriptor::RenderPipelineDescriptor(Box::new(descriptor))
+            }),
+        )
+        .map(CachedRenderPipelineId)
+        .collect()
+    }
+
+    /// Insert several compute pipelines into the cache, and queue their creation.
+    ///
+    /// This behaves like calling [`PipelineCache::queue_compute_pipeline()`] for each descriptor,
+    /// but only locks the queue once, reducing contention when many pipelines are queued at once.
+    pub fn queue_compute_pipelines_bulk(
+        &self,
+        descriptors: impl IntoIterator<Item = ComputePipelineDescriptor>,
+    ) -> Vec<CachedComputePipelineId> {
+        self.queue_pipelines_bulk(
+            descriptors.into_iter().map(|descriptor| {
+                PipelineDescriptor::ComputePipelineDescriptor(Box::new(descriptor))
+            }),
+        )
+        .map(CachedComputePipelineId)
+        .collect()
+    }
+
+    fn queue_pipelines_bulk(
+        &self,
+        descriptors: impl Iterator<Item = PipelineDescriptor>,
+    ) -> core::ops::Range<CachedPipelineId> {
+        // Collect before locking, in case the iterator queues pipelines itself.
+        let descriptors = descriptors.collect::<Vec<_>>();
+        let mut new_pipelines = self
+            .new_pipelines
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let start = self.pipelines.len() + new_pipelines.len();
+        new_pipelines.extend(
+            descriptors.into_iter().map(|descriptor| {
+                CachedPipeline::new(descriptor, self.frame.load(Ordering::Relaxed))
+            }),
+        );
+        start..self.pipelines.len() + new_pipelines.len()
+    }
+
+    /// Insert a render pipeline into the cache, queue its creation, and return a future resolving
+    /// once the pipeline was created.
+    ///
+    /// The future doesn't drive the creation itself: the pipeline is created when the cache
+    /// processes its queue, like any other pipeline. Errors that are retried, such as a shader
+    /// not being loaded yet, don't complete the future.
+    ///
+    /// The future never completes if the pipeline is removed from the cache before being created.
+    pub fn queue_render_pipeline_async(
+        &self,
+        descriptor: RenderPipelineDescriptor,
+    ) -> impl Future<Output = Result<CachedRenderPipelineId, PipelineCacheError>> + use<> {
+        let id = self.queue_render_pipeline(descriptor);
+        let created = self.wait_for_pipeline(id.0);
+        async move { created.await.map(|()| id) }
+    }
+
+    /// Insert a compute pipeline into the cache, queue its creation, and return a future resolving
+    /// once the pipeline was created.
+    ///
+    /// See [`PipelineCache::queue_render_pipeline_async()`] for details.
+    pub fn queue_compute_pipeline_async(
+        &self,
+        descriptor: ComputePipelineDescriptor,
+    ) -> impl Future<Output = Result<CachedComputePipelineId, PipelineCacheError>> + use<> {
+        let id = self.queue_compute_pipeline(descriptor);
+        let created = self.wait_for_pipeline(id.0);
+        async move { created.await.map(|()| id) }
+    }
+
+    /// Insert a compute pipeline into the cache, and queue its creation.
+    ///
+    /// The pipeline is always inserted and queued for creation. There is no attempt to deduplicate it with
+    /// an already cached pipeline.
+    ///
+    /// # Returns
+    ///
+    /// This method returns the unique compute shader ID of the cached pipeline, which can be used to query
+    /// the caching state with [`get_compute_pipeline_state()`] and to retrieve the created GPU pipeline once
+    /// it's ready with [`get_compute_pipeline()`].
+    ///
+    /// [`get_compute_pipeline_state()`]: PipelineCache::get_compute_pipeline_state
+    /// [`get_compute_pipeline()`]: PipelineCache::get_compute_pipeline
+    pub fn queue_compute_pipeline(
+        &self,
+        descriptor: ComputePipelineDescriptor,
+    ) -> CachedComputePipelineId {
+        self.queue_compute_pipeline_with_hint(descriptor, CompilationHint::SystemDefault)
+    }
+
+    /// Insert a compute pipeline into the cache, and queue its creation with the given
+    /// [`CompilationHint`].
+    ///
+    /// See [`PipelineCache::queue_render_pipeline_with_hint()`].
+    pub fn queue_compute_pipeline_with_hint(
+        &self,
+        descriptor: ComputePipelineDescriptor,
+        hint: CompilationHint,
+    ) -> CachedComputePipelineId {
+        CachedComputePipelineId(self.queue_pipeline_with_hint(
+            PipelineDescriptor::ComputePipelineDescriptor(Box::new(descriptor)),
+            hint,
+        ))
+    }
+
+    /// Insert a render pipeline into the cache and queue its creation, grouping it with the other
+    /// pipelines queued with the same `tag`.
+    ///
+    /// All pipelines of a tag can then be invalidated at once with
+    /// [`PipelineCache::invalidate_tag()`], for example when the plugin or asset pack that queued
+    /// them is unloaded. Tags are compared by type and hash. See
+    /// [`PipelineCache::queue_render_pipeline()`].
+    pub fn queue_render_pipeline_tagged(
+        &self,
+        descriptor: RenderPipelineDescriptor,
+        tag: impl Hash + Eq + Send + 'static,
+    ) -> CachedRenderPipelineId {
+        let id = self.queue_render_pipeline(descriptor);
+        self.tag_pipeline(id.0, &tag);
+        id
+    }
+
+    /// Insert a compute pipeline into the cache and queue its creation, grouping it with the
+    /// other pipelines queued with the same `tag`.
+    ///
+    /// See [`PipelineCache::queue_render_pipeline_tagged()`].
+    pub fn queue_compute_pipeline_tagged(
+        &self,
+        descriptor: ComputePipelineDescriptor,
+        tag: impl Hash + Eq + Send + 'static,
+    ) -> CachedComputePipelineId {
+        let id = self.queue_compute_pipeline(descriptor);
+        self.tag_pipeline(id.0, &tag);
+        id
+    }
+
+    fn tag_pipeline<T: Hash + 'static>(&self, id: CachedPipelineId, tag: &T) {
+        self.tagged_pipelines
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(tag_key(tag))
+            .or_default()
+            .push(id);
+    }
+
+    /// Resets all pipelines queued with `tag` to [`CachedPipelineState::Queued`], dropping their
+    /// GPU objects, so they are created again from their descriptors.
+    ///
+    /// Pipelines that are still being created are cancelled, and removed pipelines are skipped.
+    pub fn invalidate_tag<T: Hash + Eq + 'static>(&mut self, tag: &T) {
+        let Some(ids) = self
+            .tagged_pipelines
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&tag_key(tag))
+            .cloned()
+        else {
+            return;
+        };
+        for id in ids {
+            // Pipelines that weren't processed yet are still queued.
+            let Some(pipeline) = self.pipelines.get_mut(id) else {
+                continue;
+            };
+            if matches!(pipeline.state, CachedPipelineState::Evicted) {
+                continue;
+            }
+            pipeline.state = CachedPipelineState::Queued;
+            self.waiting_pipelines.insert(id);
+        }
+    }
+
+    /// Insert a render pipeline into the cache and queue its creation, unless a structurally
+    /// identical descriptor was already queued with this method.
     ///
-    /// The pipeline is always inserted and queued for creation. There is no attempt to deduplicate it with
-    /// an already cached pipeline.
+    /// Descriptors are looked up by hash and then compared for equality, covering shaders, shader
+    /// defs, vertex layouts, blend states and all other fields. Pipelines queued with [`PipelineCache::queue_render_pipeline()`]
+    /// are never deduplicated, so that method can still be used to intentionally create
+    /// duplicate pipelines.
     ///
     /// # Returns
     ///
-    /// This method returns the unique render shader ID of the cached pipeline, which can be used to query
-    /// the caching state with [`get_render_pipeline_state()`] and to retrieve the created GPU pipeline once
-    /// it's ready with [`get_render_pipeline()`].
-    ///
-    /// [`get_render_pipeline_state()`]: PipelineCache::get_render_pipeline_state
-    /// [`get_render_pipeline()`]: PipelineCache::get_render_pipeline
-    pub fn queue_render_pipeline(
+    /// This method returns the ID of the previously queued identical pipeline if any, or the ID of
+    /// the newly queued pipeline otherwise.
+    pub fn queue_render_pipeline_dedup(
         &self,
         descriptor: RenderPipelineDescriptor,
     ) -> CachedRenderPipelineId {
-        let mut new_pipelines = self
-            .new_pipelines
+        let hash = FixedHasher.hash_one(&descriptor);
+        let mut deduplicated_render_pipelines = self
+            .deduplicated_render_pipelines
             .lock()
             .unwrap_or_else(PoisonError::into_inner);
-        let id = CachedRenderPipelineId(self.pipelines.len() + new_pipelines.len());
-        new_pipelines.push(CachedPipeline {
-            descriptor: PipelineDescriptor::RenderPipelineDescriptor(Box::new(descriptor)),
-            state: CachedPipelineState::Queued,
-        });
+        let candidates = deduplicated_render_pipelines.entry(hash).or_default();
+        if let Some((_, id)) = candidates
+            .iter()
+            .find(|(queued_descriptor, _)| *queued_descriptor == descriptor)
+        {
+            return *id;
+        }
+        let id = self.queue_render_pipeline(descriptor.clone());
+        candidates.push((descriptor, id));
         id
     }
 
-    /// Insert a compute pipeline into the cache, and queue its creation.
-    ///
-    /// The pipeline is always inserted and queued for creation. There is no attempt to deduplicate it with
-    /// an already cached pipeline.
-    ///
-    /// # Returns
-    ///
-    /// This method returns the unique compute shader ID of the cached pipeline, which can be used to query
-    /// the caching state with [`get_compute_pipeline_state()`] and to retrieve the created GPU pipeline once
-    /// it's ready with [`get_compute_pipeline()`].
+    /// Insert or update a named render pipeline, and queue its creation.
     ///
-    /// [`get_compute_pipeline_state()`]: PipelineCache::get_compute_pipeline_state
-    /// [`get_compute_pipeline()`]: PipelineCache::get_compute_pipeline
-    pub fn queue_compute_pipeline(
+    /// The first call for a given `name` behaves like [`PipelineCache::queue_render_pipeline()`].
+    /// Later calls with the same `name` return the same ID: if the descriptor changed, the cached
+    /// pipeline is updated in place, its previous GPU object is discarded and it is queued for
+    /// creation again. This is useful for pipelines that depend on dynamic parameters, as it
+    /// avoids having to track a new ID every time they change.
+    pub fn queue_named_render_pipeline(
         &self,
-        descriptor: ComputePipelineDescriptor,
-    ) -> CachedComputePipelineId {
-        let mut new_pipelines = self
-            .new_pipelines
+        name: &str,
+        descriptor: RenderPipelineDescriptor,
+    ) -> CachedRenderPipelineId {
+        let mut named_render_pipelines = self
+            .named_render_pipelines
             .lock()
             .unwrap_or_else(PoisonError::into_inner);
-        let id = CachedComputePipelineId(self.pipelines.len() + new_pipelines.len());
-        new_pipelines.push(CachedPipeline {
-            descriptor: PipelineDescriptor::ComputePipelineDescriptor(Box::new(descriptor)),
-            state: CachedPipelineState::Queued,
-        });
+        if let Some((id, current_descriptor)) = named_render_pipelines.get_mut(name) {
+            if *current_descriptor != descriptor {
+                *current_descriptor = descriptor.clone();
+                self.updated_pipelines
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .push((
+                        id.0,
+                        PipelineDescriptor::RenderPipelineDescriptor(Box::new(descriptor)),
+                    ));
+            }
+            return *id;
+        }
+
+        let id = self.queue_render_pipeline(descriptor.clone());
+        named_render_pipelines.insert(name.to_owned(), (id, descriptor));
         id
     }
 
@@ -482,32 +2123,176 @@ impl PipelineCache {
             .get(&self.device, bind_group_layout_descriptor.clone())
     }
 
-    fn set_shader(&mut self, id: AssetId<Shader>, shader: Shader) {
-        let mut shader_cache = self.shader_cache.lock().unwrap();
-        let pipelines_to_queue = shader_cache.set_shader(id, shader);
-        for cached_pipeline in pipelines_to_queue {
-            self.pipelines[cached_pipeline].state = CachedPipelineState::Queued;
-            self.waiting_pipelines.insert(cached_pipeline);
+    /// Returns the [`PipelineLayout`] for the given bind group layouts and push constant ranges,
+    /// creating it if no pipeline uses an identical layout yet.
+    ///
+    /// Pipeline builders that create their pipelines outside of the [`PipelineCache`] can use this
+    /// to share layouts with the pipelines managed by the cache.
+    ///
+    /// Returns [`PipelineCacheError::LayoutCreationFailed`] if the layout is invalid for the
+    /// device, for example because it uses too many bind groups.
+    pub fn get_or_insert_pipeline_layout(
+        &self,
+        bind_group_layouts: &[BindGroupLayout],
+        push_constant_ranges: &[PushConstantRange],
+    ) -> Result<Arc<WgpuWrapper<PipelineLayout>>, PipelineCacheError> {
+        self.layout_cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(
+                &self.device,
+                bind_group_layouts,
+                push_constant_ranges.to_vec(),
+            )
+    }
+
+    /// The number of distinct pipeline layouts created so far.
+    pub fn pipeline_layout_count(&self) -> usize {
+        self.layout_cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// The number of times a pipeline reused an existing pipeline layout instead of creating a
+    /// new one, and the number of times a new layout had to be created.
+    pub fn pipeline_layout_cache_stats(&self) -> PipelineLayoutCacheStats {
+        let layout_cache = self
+            .layout_cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        PipelineLayoutCacheStats {
+            hits: layout_cache.hit_count(),
+            misses: layout_cache.miss_count(),
         }
     }
 
+    /// Adds a shader that isn't loaded through the asset system, so that other shaders can import
+    /// it with `#import key`, and returns its id.
+    ///
+    /// This lets plugins provide shader libraries without loading them as assets. Pipelines can
+    /// also use the shader directly by its id, e.g. with [`Handle::Uuid`] using the UUID of the
+    /// returned id. Like shader assets, the shader is processed with the global shader defs and
+    /// those of the registered [`ShaderDefProvider`]s.
+    ///
+    /// # Panics
+    ///
+    /// Panics if another shader already uses `key` as its import path.
+    pub fn add_inline_shader(&mut self, key: impl Into<String>, shader: Shader) -> AssetId<Shader> {
+        let key = key.into();
+        let id = bevy_shader::inline_shader_id(&key);
+        self.inline_shaders.insert(key.clone(), shader.clone());
+        let shader = self.with_global_shader_defs(shader);
+        let pipelines_to_queue = self.shader_cache.write().add_inline_shader(key, shader);
+        self.queue_pipelines_using_changed_shader(pipelines_to_queue);
+        id
+    }
+
+    /// Adds the global shader defs, including those of the registered [`ShaderDefProvider`]s, to
+    /// the shader defs of `shader`.
+    fn with_global_shader_defs(&self, mut shader: Shader) -> Shader {
+        shader
+            .shader_defs
+            .extend(self.global_shader_defs.iter().cloned());
+        shader
+            .shader_defs
+            .extend(self.provided_shader_defs.iter().cloned());
+        shader
+    }
+
+    fn set_shader(&mut self, id: AssetId<Shader>, shader: Shader) {
+        let pipelines_to_queue = self.shader_cache.write().set_shader(id, shader);
+        self.queue_pipelines_using_changed_shader(pipelines_to_queue);
+    }
+
     fn remove_shader(&mut self, shader: AssetId<Shader>) {
-        let mut shader_cache = self.shader_cache.lock().unwrap();
-        let pipelines_to_queue = shader_cache.remove(shader);
+        let pipelines_to_queue = self.shader_cache.write().remove(shader);
+        self.queue_pipelines_using_changed_shader(pipelines_to_queue);
+    }
+
+    /// Removes a shader from the cache and synchronously drops the GPU objects of every pipeline
+    /// using it.
+    ///
+    /// Unlike the removal of a [`Shader`] asset, which keeps the created pipelines in use until
+    /// their replacement is ready, this blocks on the creation tasks of the dependent pipelines
+    /// and discards their results, so that all their GPU objects are released when this returns.
+    /// The dependent pipelines are queued again, and are created once the shader is added back.
+    pub fn remove_shader_and_wait(&mut self, shader_id: AssetId<Shader>) {
+        let dependent_pipelines = self.shader_cache.write().remove(shader_id);
+        for id in dependent_pipelines {
+            let state = &mut self.pipelines[id].state;
+            if matches!(state, CachedPipelineState::Evicted) {
+                continue;
+            }
+            match mem::replace(state, CachedPipelineState::Queued) {
+                CachedPipelineState::Creating(task)
+                | CachedPipelineState::HotReloading(Some(task), _) => {
+                    // The task can't be cancelled while the GPU object is being created, so wait
+                    // for it and drop whatever it produced.
+                    drop(bevy_tasks::block_on(*task));
+                }
+                previous => drop(previous),
+            }
+            self.waiting_pipelines.insert(id);
+        }
+    }
+
+    fn queue_pipelines_using_changed_shader(&mut self, pipelines_to_queue: Vec<CachedPipelineId>) {
         for cached_pipeline in pipelines_to_queue {
-            self.pipelines[cached_pipeline].state = CachedPipelineState::Queued;
+            let state = &mut self.pipelines[cached_pipeline].state;
+            if matches!(state, CachedPipelineState::Evicted) {
+                continue;
+            }
+            *state = match mem::replace(state, CachedPipelineState::Queued) {
+                // Keep the created pipeline in use until its replacement is ready. The new
+                // pipeline is created by `process_queue`, like any other queued pipeline.
+                CachedPipelineState::Ok(pipeline)
+                | CachedPipelineState::HotReloading(_, pipeline) => {
+                    CachedPipelineState::HotReloading(None, pipeline)
+                }
+                _ => CachedPipelineState::Queued,
+            };
             self.waiting_pipelines.insert(cached_pipeline);
         }
     }
 
+    /// Whether a pipeline with the given [`CompilationHint`] is compiled synchronously.
+    fn is_synchronous(&self, hint: CompilationHint) -> bool {
+        match hint {
+            CompilationHint::Async => false,
+            CompilationHint::Synchronous => true,
+            CompilationHint::SystemDefault => self.synchronous_pipeline_compilation,
+        }
+    }
+
     fn start_create_render_pipeline(
         &mut self,
         id: CachedPipelineId,
-        descriptor: RenderPipelineDescriptor,
+        mut descriptor: RenderPipelineDescriptor,
+        sync: bool,
     ) -> CachedPipelineState {
+        if descriptor.multiview.is_some() {
+            descriptor.vertex.shader_defs.push("MULTIVIEW".into());
+            if let Some(fragment) = &mut descriptor.fragment {
+                fragment.shader_defs.push("MULTIVIEW".into());
+            }
+        }
+
+        if descriptor.label.is_none() {
+            let mut label = format!("auto:{}", shader_label(&descriptor.vertex.shader));
+            if let Some(fragment) = &descriptor.fragment
+                && fragment.shader != descriptor.vertex.shader
+            {
+                label.push('+');
+                label.push_str(&shader_label(&fragment.shader));
+            }
+            descriptor.label = Some(label.into());
+        }
+
         let device = self.device.clone();
         let shader_cache = self.shader_cache.clone();
         let layout_cache = self.layout_cache.clone();
+        let wgpu_pipeline_cache = self.wgpu_pipeline_cache.clone();
         let mut bindgroup_layout_cache = self.bindgroup_layout_cache.lock().unwrap();
         let bind_group_layout = descriptor
             .layout
@@ -519,14 +2304,13 @@ impl PipelineCache {
 
         create_pipeline_task(
             async move {
-                let mut shader_cache = shader_cache.lock().unwrap();
-                let mut layout_cache = layout_cache.lock().unwrap();
-
-                let vertex_module = match shader_cache.get(
+                let vertex_module = match get_shader_module(
+                    &shader_cache,
                     &device,
                     id,
-                    descriptor.vertex.shader.id(),
+                    &descriptor.vertex.shader,
                     &descriptor.vertex.shader_defs,
+                    descriptor.vertex.entry_point.as_deref(),
                 ) {
                     Ok(module) => module,
                     Err(err) => return Err(err),
@@ -534,11 +2318,13 @@ impl PipelineCache {
 
                 let fragment_module = match &descriptor.fragment {
                     Some(fragment) => {
-                        match shader_cache.get(
+                        match get_shader_module(
+                            &shader_cache,
                             &device,
                             id,
-                            fragment.shader.id(),
+                            &fragment.shader,
                             &fragment.shader_defs,
+                            fragment.entry_point.as_deref(),
                         ) {
                             Ok(module) => Some(module),
                             Err(err) => return Err(err),
@@ -547,18 +2333,23 @@ impl PipelineCache {
                     None => None,
                 };
 
+                let mut layout_cache = layout_cache.lock().unwrap();
+
                 let layout =
                     if descriptor.layout.is_empty() && descriptor.push_constant_ranges.is_empty() {
                         None
                     } else {
-                        Some(layout_cache.get(
+                        match layout_cache.get(
                             &device,
                             &bind_group_layout,
                             descriptor.push_constant_ranges.to_vec(),
-                        ))
+                        ) {
+                            Ok(layout) => Some(layout),
+                            Err(err) => return Err(err),
+                        }
                     };
 
-                drop((shader_cache, layout_cache));
+                drop(layout_cache);
 
                 let vertex_buffer_layouts = descriptor
                     .vertex
@@ -579,14 +2370,19 @@ impl PipelineCache {
                     )
                 });
 
-                // TODO: Expose the rest of this somehow
+                let constants = descriptor
+                    .constants
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), *value))
+                    .collect::<Vec<_>>();
+
                 let compilation_options = PipelineCompilationOptions {
-                    constants: &[],
+                    constants: &constants,
                     zero_initialize_workgroup_memory: descriptor.zero_initialize_workgroup_memory,
                 };
 
                 let descriptor = RawRenderPipelineDescriptor {
-                    multiview: None,
+                    multiview: descriptor.multiview,
                     depth_stencil: descriptor.depth_stencil.clone(),
                     label: descriptor.label.as_deref(),
                     layout: layout.as_ref().map(|layout| -> &PipelineLayout { layout }),
@@ -608,25 +2404,34 @@ impl PipelineCache {
                             // TODO: Should this be the same as the vertex compilation options?
                             compilation_options,
                         }),
-                    cache: None,
+                    cache: wgpu_pipeline_cache
+                        .as_ref()
+                        .map(|cache| -> &wgpu::PipelineCache { cache }),
                 };
 
                 Ok(Pipeline::RenderPipeline(
                     device.create_render_pipeline(&descriptor),
                 ))
             },
-            self.synchronous_pipeline_compilation,
+            sync,
+            self.compilation_time_us.clone(),
         )
     }
 
     fn start_create_compute_pipeline(
         &mut self,
         id: CachedPipelineId,
-        descriptor: ComputePipelineDescriptor,
+        mut descriptor: ComputePipelineDescriptor,
+        sync: bool,
     ) -> CachedPipelineState {
+        if descriptor.label.is_none() {
+            descriptor.label = Some(format!("auto:{}", shader_label(&descriptor.shader)).into());
+        }
+
         let device = self.device.clone();
         let shader_cache = self.shader_cache.clone();
         let layout_cache = self.layout_cache.clone();
+        let wgpu_pipeline_cache = self.wgpu_pipeline_cache.clone();
         let mut bindgroup_layout_cache = self.bindgroup_layout_cache.lock().unwrap();
         let bind_group_layout = descriptor
             .layout
@@ -638,51 +2443,63 @@ impl PipelineCache {
 
         create_pipeline_task(
             async move {
-                let mut shader_cache = shader_cache.lock().unwrap();
-                let mut layout_cache = layout_cache.lock().unwrap();
-
-                let compute_module = match shader_cache.get(
+                let compute_module = match get_shader_module(
+                    &shader_cache,
                     &device,
                     id,
-                    descriptor.shader.id(),
+                    &descriptor.shader,
                     &descriptor.shader_defs,
+                    descriptor.entry_point.as_deref(),
                 ) {
                     Ok(module) => module,
                     Err(err) => return Err(err),
                 };
 
+                let mut layout_cache = layout_cache.lock().unwrap();
+
                 let layout =
                     if descriptor.layout.is_empty() && descriptor.push_constant_ranges.is_empty() {
                         None
                     } else {
-                        Some(layout_cache.get(
+                        match layout_cache.get(
                             &device,
                             &bind_group_layout,
                             descriptor.push_constant_ranges.to_vec(),
-                        ))
+                        ) {
+                            Ok(layout) => Some(layout),
+                            Err(err) => return Err(err),
+                        }
                     };
 
-                drop((shader_cache, layout_cache));
+                drop(layout_cache);
+
+                let constants = descriptor
+                    .constants
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), *value))
+                    .collect::<Vec<_>>();
 
                 let descriptor = RawComputePipelineDescriptor {
                     label: descriptor.label.as_deref(),
                     layout: layout.as_ref().map(|layout| -> &PipelineLayout { layout }),
                     module: &compute_module,
                     entry_point: descriptor.entry_point.as_deref(),
-                    // TODO: Expose the rest of this somehow
                     compilation_options: PipelineCompilationOptions {
-                        constants: &[],
+                        constants: &constants,
                         zero_initialize_workgroup_memory: descriptor
                             .zero_initialize_workgroup_memory,
                     },
-                    cache: None,
+                    cache: wgpu_pipeline_cache
+                        .as_ref()
+                        .map(|cache| -> &wgpu::PipelineCache { cache }),
                 };
 
                 Ok(Pipeline::ComputePipeline(
                     device.create_compute_pipeline(&descriptor),
                 ))
             },
-            self.synchronous_pipeline_compilation,
+            sync,
+            self.compilation_time_us.clone(),
         )
     }
 
@@ -703,74 +2520,236 @@ impl PipelineCache {
                 .unwrap_or_else(PoisonError::into_inner);
             for new_pipeline in new_pipelines.drain(..) {
                 let id = pipelines.len();
+                *new_pipeline
+                    .stats_category
+                    .count(&mut self.compilation_stats) += 1;
                 pipelines.push(new_pipeline);
                 waiting_pipelines.insert(id);
             }
+
+            let mut updated_pipelines = self
+                .updated_pipelines
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            for (id, descriptor) in updated_pipelines.drain(..) {
+                // Removed pipelines stay removed, even if they were updated in the meantime.
+                if matches!(pipelines[id].state, CachedPipelineState::Evicted) {
+                    continue;
+                }
+                pipelines[id].descriptor = descriptor;
+                pipelines[id].state = CachedPipelineState::Queued;
+                waiting_pipelines.insert(id);
+            }
         }
 
-        for id in waiting_pipelines {
+        // Poll in-flight compilations first, so that finished work is harvested before any new
+        // task is started, and the number of compilations still running is known.
+        let (creating, waiting_pipelines): (Vec<_>, Vec<_>) =
+            waiting_pipelines.into_iter().partition(|&id| {
+                matches!(
+                    pipelines[id].state,
+                    CachedPipelineState::Creating(_)
+                        | CachedPipelineState::HotReloading(Some(_), _)
+                )
+            });
+        self.compiling_pipelines = 0;
+        for id in creating.into_iter().chain(waiting_pipelines) {
             self.process_pipeline(&mut pipelines[id], id);
+            pipelines[id].update_stats(&mut self.compilation_stats);
         }
 
         self.pipelines = pipelines;
     }
 
+    /// Adds the pipelines created during this frame to the history
+    /// [`PipelineCache::compilation_rate()`] is averaged over.
+    fn end_compilation_frame(&mut self) {
+        if self.compilation_history.len() == COMPILATION_RATE_WINDOW {
+            self.compilation_history.pop_front();
+        }
+        self.compilation_history
+            .push_back((Instant::now(), mem::take(&mut self.completed_this_frame)));
+    }
+
     fn process_pipeline(&mut self, cached_pipeline: &mut CachedPipeline, id: usize) {
         match &mut cached_pipeline.state {
             CachedPipelineState::Queued => {
-                cached_pipeline.state = match &cached_pipeline.descriptor {
+                if self.compilation_limit_reached() {
+                    // Wait for a running compilation to finish.
+                    self.waiting_pipelines.insert(id);
+                    return;
+                }
+                cached_pipeline.compilation_started_at = Some(Instant::now());
+                cached_pipeline.compilation_duration = None;
+                self.compilation_event(id, CompilationPhase::Started);
+                let sync = self.is_synchronous(cached_pipeline.compilation_hint);
+                let state = match &cached_pipeline.descriptor {
                     PipelineDescriptor::RenderPipelineDescriptor(descriptor) => {
-                        self.start_create_render_pipeline(id, *descriptor.clone())
+                        self.start_create_render_pipeline(id, *descriptor.clone(), sync)
                     }
                     PipelineDescriptor::ComputePipelineDescriptor(descriptor) => {
-                        self.start_create_compute_pipeline(id, *descriptor.clone())
+                        self.start_create_compute_pipeline(id, *descriptor.clone(), sync)
                     }
                 };
+                // Synchronous compilation creates the pipeline right away.
+                let result = match state {
+                    CachedPipelineState::Creating(_) => {
+                        cached_pipeline.state = state;
+                        self.compiling_pipelines += 1;
+                        self.waiting_pipelines.insert(id);
+                        return;
+                    }
+                    CachedPipelineState::Ok(pipeline) => Ok(pipeline),
+                    CachedPipelineState::Err(err) => Err(err),
+                    _ => unreachable!("pipeline creation either started or finished"),
+                };
+                if self.finish_compilation(cached_pipeline, id, result) {
+                    return;
+                }
+            }
+
+            CachedPipelineState::Creating(task) => {
+                match bevy_tasks::futures::check_ready(&mut **task) {
+                    Some(result) => {
+                        if self.finish_compilation(cached_pipeline, id, result) {
+                            return;
+                        }
+                    }
+                    None => self.compiling_pipelines += 1,
+                }
             }
 
-            CachedPipelineState::Creating(task) => match bevy_tasks::futures::check_ready(task) {
-                Some(Ok(pipeline)) => {
-                    cached_pipeline.state = CachedPipelineState::Ok(pipeline);
+            CachedPipelineState::HotReloading(None, _) => {
+                if self.compilation_limit_reached() {
+                    // Wait for a running compilation to finish.
+                    self.waiting_pipelines.insert(id);
                     return;
                 }
-                Some(Err(err)) => cached_pipeline.state = CachedPipelineState::Err(err),
-                _ => (),
-            },
+                cached_pipeline.compilation_started_at = Some(Instant::now());
+                cached_pipeline.compilation_duration = None;
+                self.compilation_event(id, CompilationPhase::Started);
+                let sync = self.is_synchronous(cached_pipeline.compilation_hint);
+                let state = match &cached_pipeline.descriptor {
+                    PipelineDescriptor::RenderPipelineDescriptor(descriptor) => {
+                        self.start_create_render_pipeline(id, *descriptor.clone(), sync)
+                    }
+                    PipelineDescriptor::ComputePipelineDescriptor(descriptor) => {
+                        self.start_create_compute_pipeline(id, *descriptor.clone(), sync)
+                    }
+                };
+                let result = match state {
+                    CachedPipelineState::Creating(task) => {
+                        if let CachedPipelineState::HotReloading(pending, _) =
+                            &mut cached_pipeline.state
+                        {
+                            *pending = Some(task);
+                        }
+                        self.compiling_pipelines += 1;
+                        self.waiting_pipelines.insert(id);
+                        return;
+                    }
+                    CachedPipelineState::Ok(pipeline) => Ok(pipeline),
+                    CachedPipelineState::Err(err) => Err(err),
+                    _ => unreachable!("pipeline creation either started or finished"),
+                };
+                if self.finish_hot_reload(cached_pipeline, id, result) {
+                    return;
+                }
+            }
+
+            CachedPipelineState::HotReloading(Some(task), _) => {
+                match bevy_tasks::futures::check_ready(&mut **task) {
+                    Some(result) => {
+                        if self.finish_hot_reload(cached_pipeline, id, result) {
+                            return;
+                        }
+                    }
+                    None => self.compiling_pipelines += 1,
+                }
+            }
 
             CachedPipelineState::Err(err) => match err {
                 // Retry
-                PipelineCacheError::ShaderNotLoaded(_)
+                PipelineCacheError::ShaderNotLoaded(..)
                 | PipelineCacheError::ShaderImportNotYetAvailable => {
                     cached_pipeline.state = CachedPipelineState::Queued;
                 }
-
-                // Shader could not be processed ... retrying won't help
-                PipelineCacheError::ProcessShaderError(err) => {
-                    let error_detail =
-                        err.emit_to_string(&self.shader_cache.lock().unwrap().composer);
-                    if std::env::var("VERBOSE_SHADER_ERROR")
-                        .is_ok_and(|v| !(v.is_empty() || v == "0" || v == "false"))
-                    {
-                        error!("{}", pipeline_error_context(cached_pipeline));
-                    }
-                    error!("failed to process shader error:\n{}", error_detail);
-                    return;
-                }
-                PipelineCacheError::CreateShaderModule(description) => {
-                    error!("failed to create shader module: {}", description);
-                    return;
-                }
+                // Errors that retrying won't fix were reported when the creation failed.
+                _ => return,
             },
 
-            CachedPipelineState::Ok(_) => return,
+            CachedPipelineState::Ok(_) | CachedPipelineState::Evicted => return,
         }
 
         // Retry
         self.waiting_pipelines.insert(id);
     }
 
-    pub(crate) fn process_pipeline_queue_system(mut cache: ResMut<Self>) {
+    /// Ends the creation of the replacement of a hot reloaded pipeline, returning `false` if it
+    /// must be retried.
+    fn finish_hot_reload(
+        &mut self,
+        cached_pipeline: &mut CachedPipeline,
+        id: CachedPipelineId,
+        result: Result<Pipeline, PipelineCacheError>,
+    ) -> bool {
+        let phase = cached_pipeline.finish_hot_reload(result);
+        self.compilation_event(id, phase);
+        if phase == CompilationPhase::Completed {
+            self.pipeline_created(ReadyPipelineId::new(id, &cached_pipeline.descriptor));
+        }
+        !matches!(
+            cached_pipeline.state,
+            CachedPipelineState::HotReloading(None, _)
+        )
+    }
+
+    pub(crate) fn process_pipeline_queue_system(
+        mut cache: ResMut<Self>,
+        mut pipeline_ready: MessageWriter<PipelineReady>,
+        mut compilation_events: MessageWriter<PipelineCompilationEvent>,
+    ) {
+        cache.has_newly_ready_pipelines = false;
+        cache.frame.fetch_add(1, Ordering::Relaxed);
         cache.process_queue();
+        cache.end_compilation_frame();
+        pipeline_ready.write_batch(cache.ready_pipelines.drain(..).map(PipelineReady));
+        compilation_events.write_batch(cache.compilation_events.drain(..));
+    }
+
+    pub(crate) fn extract_synchronous_pipeline_compilation(
+        mut cache: ResMut<Self>,
+        setting: Extract<Option<Res<SynchronousPipelineCompilation>>>,
+    ) {
+        if let Some(setting) = setting.as_ref()
+            && setting.is_changed()
+        {
+            cache.set_synchronous_pipeline_compilation(setting.0);
+        }
+    }
+
+    pub(crate) fn share_compilation_metrics_system(
+        cache: Res<Self>,
+        metrics: Res<SharedPipelineCompilationMetrics>,
+    ) {
+        *metrics.0.lock().unwrap_or_else(PoisonError::into_inner) = (
+            PipelineCompilationRate(cache.compilation_rate()),
+            cache.stats(),
+        );
+    }
+
+    pub(crate) fn update_provided_shader_defs(world: &mut World) {
+        world.resource_scope(|world, mut cache: Mut<Self>| {
+            let provided_shader_defs: Vec<_> = cache
+                .shader_def_providers
+                .iter()
+                .flat_map(|provider| provider(world))
+                .collect();
+            if provided_shader_defs != cache.provided_shader_defs {
+                cache.provided_shader_defs = provided_shader_defs;
+                cache.provided_shader_defs_changed = true;
+            }
+        });
     }
 
     pub(crate) fn extract_shaders(
@@ -778,6 +2757,18 @@ impl PipelineCache {
         shaders: Extract<Res<Assets<Shader>>>,
         mut events: Extract<MessageReader<AssetEvent<Shader>>>,
     ) {
+        // Every shader is processed with the provided shader defs, so all of them are set again
+        // to process them and requeue their pipelines with the new defs.
+        if mem::take(&mut cache.provided_shader_defs_changed) {
+            for (id, shader) in shaders.iter() {
+                let shader = cache.with_global_shader_defs(shader.clone());
+                cache.set_shader(id, shader);
+            }
+            for (key, shader) in cache.inline_shaders.clone() {
+                cache.add_inline_shader(key, shader);
+            }
+        }
+
         for event in events.read() {
             #[expect(
                 clippy::match_same_arms,
@@ -787,9 +2778,7 @@ impl PipelineCache {
                 // PERF: Instead of blocking waiting for the shader cache lock, try again next frame if the lock is currently held
                 AssetEvent::Added { id } | AssetEvent::Modified { id } => {
                     if let Some(shader) = shaders.get(*id) {
-                        let mut shader = shader.clone();
-                        shader.shader_defs.extend(cache.global_shader_defs.clone());
-
+                        let shader = cache.with_global_shader_defs(shader.clone());
                         cache.set_shader(*id, shader);
                     }
                 }
@@ -803,7 +2792,75 @@ impl PipelineCache {
     }
 }
 
-fn pipeline_error_context(cached_pipeline: &CachedPipeline) -> String {
+/// Extension methods for [`PipelineCache`] that label pipelines after the system queuing them.
+pub trait PipelineCacheExt {
+    /// Insert a render pipeline into the cache and queue its creation, labeling it with the type
+    /// name of the system `S` if the descriptor doesn't already have a label.
+    ///
+    /// The label shows up in graphics debuggers such as RenderDoc or PIX, making it easy to tell
+    /// which system a pipeline came from. `type_name` is resolved at compile time, so this has no
+    /// runtime overhead compared to [`PipelineCache::queue_render_pipeline()`].
+    fn queue_render_pipeline_from_system<S: System>(
+        &self,
+        descriptor: RenderPipelineDescriptor,
+    ) -> CachedRenderPipelineId;
+}
+
+impl PipelineCacheExt for PipelineCache {
+    fn queue_render_pipeline_from_system<S: System>(
+        &self,
+        mut descriptor: RenderPipelineDescriptor,
+    ) -> CachedRenderPipelineId {
+        if descriptor.label.is_none() {
+            descriptor.label = Some(Cow::Borrowed(core::any::type_name::<S>()));
+        }
+        self.queue_render_pipeline(descriptor)
+    }
+}
+
+/// Get a processed shader module from the cache, only taking the write lock if the shader still
+/// needs to be processed.
+fn get_shader_module(
+    shader_cache: &RwLock<ShaderCache<WgpuWrapper<ShaderModule>, RenderDevice>>,
+    device: &RenderDevice,
+    pipeline: CachedPipelineId,
+    shader: &Handle<Shader>,
+    shader_defs: &[ShaderDefVal],
+    entry_point: Option<&str>,
+) -> Result<Arc<WgpuWrapper<ShaderModule>>, PipelineCacheError> {
+    let id = shader.id();
+    if let Some(module) = shader_cache
+        .read()
+        .get_processed(pipeline, id, shader_defs, entry_point)
+    {
+        return Ok(module);
+    }
+    match shader_cache
+        .write()
+        .get(device, pipeline, id, shader_defs, entry_point)
+    {
+        // The shader cache doesn't know about shaders that aren't loaded, but their handle may
+        // still tell where they are loaded from.
+        Err(PipelineCacheError::ShaderNotLoaded(not_loaded, None)) if not_loaded == id => {
+            Err(PipelineCacheError::ShaderNotLoaded(
+                id,
+                shader
+                    .path()
+                    .map(|path| ShaderImport::AssetPath(path.to_string())),
+            ))
+        }
+        result => result,
+    }
+}
+
+/// Identifies a shader in automatically generated pipeline labels, by its asset path if it has one.
+fn shader_label(shader: &Handle<Shader>) -> String {
+    shader
+        .path()
+        .map_or_else(|| shader.id().to_string(), ToString::to_string)
+}
+
+fn pipeline_error_context(descriptor: &PipelineDescriptor) -> String {
     fn format(
         shader: &Handle<Shader>,
         entry: &Option<Cow<'static, str>>,
@@ -823,13 +2880,14 @@ fn pipeline_error_context(cached_pipeline: &CachedPipeline) -> String {
                 ShaderDefVal::Bool(k, v) if *v => Some(k.to_string()),
                 ShaderDefVal::Int(k, v) => Some(format!("{k} = {v}")),
                 ShaderDefVal::UInt(k, v) => Some(format!("{k} = {v}")),
+                ShaderDefVal::Float(k, _) => Some(format!("{k} = {}", def.value_as_string())),
                 _ => None,
             })
             .collect::<Vec<_>>()
             .join(", ");
         format!("{source}:{entry}\nshader defs: {shader_defs}")
     }
-    match &cached_pipeline.descriptor {
+    match descriptor {
         PipelineDescriptor::RenderPipelineDescriptor(desc) => {
             let vert = &desc.vertex;
             let vert_str = format(&vert.shader, &vert.entry_point, &vert.shader_defs);
@@ -853,9 +2911,13 @@ fn pipeline_error_context(cached_pipeline: &CachedPipeline) -> String {
 fn create_pipeline_task(
     task: impl Future<Output = Result<Pipeline, PipelineCacheError>> + Send + 'static,
     sync: bool,
+    compilation_time_us: Arc<AtomicU64>,
 ) -> CachedPipelineState {
+    let task = timed_pipeline_task(task, compilation_time_us);
     if !sync {
-        return CachedPipelineState::Creating(bevy_tasks::AsyncComputeTaskPool::get().spawn(task));
+        return CachedPipelineState::Creating(Box::new(
+            bevy_tasks::AsyncComputeTaskPool::get().spawn(task),
+        ));
     }
 
     match bevy_tasks::block_on(task) {
@@ -872,9 +2934,231 @@ fn create_pipeline_task(
 fn create_pipeline_task(
     task: impl Future<Output = Result<Pipeline, PipelineCacheError>> + Send + 'static,
     _sync: bool,
+    compilation_time_us: Arc<AtomicU64>,
 ) -> CachedPipelineState {
-    match bevy_tasks::block_on(task) {
+    match bevy_tasks::block_on(timed_pipeline_task(task, compilation_time_us)) {
         Ok(pipeline) => CachedPipelineState::Ok(pipeline),
         Err(err) => CachedPipelineState::Err(err),
     }
 }
+
+/// The key of a pipeline tag, see [`PipelineCache::queue_render_pipeline_tagged()`].
+fn tag_key<T: Hash + 'static>(tag: &T) -> (TypeId, u64) {
+    (TypeId::of::<T>(), FixedHasher.hash_one(tag))
+}
+
+/// Adds the time spent running `task` to `compilation_time_us`.
+fn timed_pipeline_task(
+    task: impl Future<Output = Result<Pipeline, PipelineCacheError>> + Send + 'static,
+    compilation_time_us: Arc<AtomicU64>,
+) -> impl Future<Output = Result<Pipeline, PipelineCacheError>> + Send + 'static {
+    async move {
+        let start = Instant::now();
+        let result = task.await;
+        compilation_time_us.fetch_add(start.elapsed().as_micros() as u64, Ordering::Relaxed);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MainWorld;
+    use bevy_ecs::{message::Messages, system::RunSystemOnce};
+
+    /// A pipeline cache on a device of wgpu's no-op backend, which accepts every call without
+    /// needing a GPU.
+    fn test_pipeline_cache() -> PipelineCache {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+            backends: wgpu::Backends::NOOP,
+            backend_options: wgpu::BackendOptions {
+                noop: wgpu::NoopBackendOptions { enable: true },
+                ..default()
+            },
+            ..default()
+        });
+        let adapter = bevy_tasks::block_on(instance.request_adapter(&default())).unwrap();
+        let (device, _queue) = bevy_tasks::block_on(adapter.request_device(&default())).unwrap();
+        PipelineCache::new(
+            RenderDevice::new(WgpuWrapper::new(device)),
+            RenderAdapter(Arc::new(WgpuWrapper::new(adapter))),
+            true,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn cached_pipeline_ids_are_ordered_by_insertion() {
+        let cache = test_pipeline_cache();
+        let first = cache.queue_compute_pipeline(default());
+        let second = cache.queue_compute_pipeline(default());
+        assert!(first < second);
+        assert!(second < CachedComputePipelineId::INVALID);
+
+        let mut ids = [second, CachedComputePipelineId::INVALID, first];
+        ids.sort();
+        assert_eq!(ids, [first, second, CachedComputePipelineId::INVALID]);
+    }
+
+    #[test]
+    fn descriptors_differing_in_constant_values_are_distinct() {
+        let descriptor = |value: f64| ComputePipelineDescriptor {
+            constants: vec![("SCALE".to_string(), value)],
+            ..default()
+        };
+        assert_eq!(descriptor(1.0), descriptor(1.0));
+        assert_ne!(descriptor(1.0), descriptor(2.0));
+        assert_ne!(
+            FixedHasher.hash_one(descriptor(1.0)),
+            FixedHasher.hash_one(descriptor(2.0))
+        );
+    }
+
+    #[test]
+    fn removed_named_pipeline_ignores_pending_update() {
+        let mut cache = test_pipeline_cache();
+        let id = cache.queue_named_render_pipeline("named", default());
+        let updated = cache.queue_named_render_pipeline(
+            "named",
+            RenderPipelineDescriptor {
+                label: Some("updated".into()),
+                ..default()
+            },
+        );
+        assert_eq!(id, updated);
+
+        cache.remove_render_pipeline(id);
+        cache.process_queue();
+        assert!(matches!(
+            cache.get_render_pipeline_state(id),
+            CachedPipelineState::Evicted
+        ));
+        assert!(!cache.waiting_pipelines().any(|waiting| waiting == id.id()));
+    }
+
+    #[test]
+    fn blocking_on_a_pipeline_emits_started_and_completed_events() {
+        bevy_tasks::AsyncComputeTaskPool::get_or_init(bevy_tasks::TaskPool::default);
+        let mut cache = test_pipeline_cache();
+        cache.set_synchronous_pipeline_compilation(false);
+        let shader = Handle::<Shader>::Uuid(
+            bevy_asset::uuid::Uuid::from_u128(1),
+            core::marker::PhantomData,
+        );
+        cache.set_shader(
+            shader.id(),
+            Shader::from_wgsl("@compute @workgroup_size(64) fn main() {}", "test.wgsl"),
+        );
+
+        let id = cache
+            .get_compute_pipeline_blocking(ComputePipelineDescriptor {
+                shader,
+                ..default()
+            })
+            .unwrap();
+        let phases: Vec<_> = cache
+            .compilation_events
+            .iter()
+            .filter(|event| event.id == id.id())
+            .map(|event| event.phase)
+            .collect();
+        assert_eq!(
+            phases,
+            [CompilationPhase::Started, CompilationPhase::Completed]
+        );
+    }
+
+    #[test]
+    fn stats_follow_pipeline_state_changes() {
+        let mut cache = test_pipeline_cache();
+        let shader = Handle::<Shader>::Uuid(
+            bevy_asset::uuid::Uuid::from_u128(1),
+            core::marker::PhantomData,
+        );
+        let descriptor = ComputePipelineDescriptor {
+            shader: shader.clone(),
+            ..default()
+        };
+        let ready = cache.queue_compute_pipeline(descriptor.clone());
+        let removed = cache.queue_compute_pipeline(descriptor);
+        assert_eq!(cache.stats().queued, 2);
+
+        // The shader isn't loaded yet, so creating the pipelines fails and is retried.
+        cache.process_queue();
+        assert_eq!(cache.stats().errored, 2);
+
+        cache.set_shader(
+            shader.id(),
+            Shader::from_wgsl("@compute @workgroup_size(64) fn main() {}", "test.wgsl"),
+        );
+        cache.remove_compute_pipeline(removed);
+        cache.process_queue();
+        cache.process_queue();
+        let stats = cache.stats();
+        assert_eq!(
+            (
+                stats.queued,
+                stats.creating,
+                stats.ready,
+                stats.errored,
+                stats.evicted
+            ),
+            (0, 0, 1, 0, 1)
+        );
+        assert!(cache.get_compute_pipeline(ready).is_some());
+    }
+
+    #[derive(Resource)]
+    struct TestShaderDefs(u32);
+
+    impl ShaderDefProvider for TestShaderDefs {
+        fn shader_defs(&self) -> Vec<ShaderDefVal> {
+            vec![ShaderDefVal::UInt("TEST_VALUE".to_string(), self.0)]
+        }
+    }
+
+    #[test]
+    fn changed_provided_shader_defs_requeue_pipelines() {
+        let mut cache = test_pipeline_cache();
+        cache.register_shader_def_provider::<TestShaderDefs>();
+
+        let mut shaders = Assets::<Shader>::default();
+        let shader = shaders.add(Shader::from_wgsl(
+            "const VALUE: u32 = #{TEST_VALUE}; @compute @workgroup_size(64) fn main() {}",
+            "test.wgsl",
+        ));
+        let mut main_world = World::new();
+        main_world.insert_resource(shaders);
+        main_world.init_resource::<Messages<AssetEvent<Shader>>>();
+
+        let mut world = World::new();
+        world.insert_resource(MainWorld(main_world));
+        world.insert_resource(TestShaderDefs(1));
+        world.insert_resource(cache);
+        let extract = |world: &mut World| {
+            PipelineCache::update_provided_shader_defs(world);
+            world
+                .run_system_once(PipelineCache::extract_shaders)
+                .unwrap();
+        };
+
+        extract(&mut world);
+        let mut cache = world.resource_mut::<PipelineCache>();
+        let id = cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            shader,
+            ..default()
+        });
+        cache.process_queue();
+        assert!(cache.get_compute_pipeline(id).is_some());
+
+        world.resource_mut::<TestShaderDefs>().0 = 2;
+        extract(&mut world);
+        assert!(matches!(
+            world
+                .resource::<PipelineCache>()
+                .get_compute_pipeline_state(id),
+            CachedPipelineState::HotReloading(None, _)
+        ));
+    }
+}
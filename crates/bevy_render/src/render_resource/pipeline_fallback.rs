@@ -0,0 +1,156 @@
+use crate::{
+    render_resource::{
+        CachedRenderPipelineId, ColorTargetState, ColorWrites, FragmentState, PipelineCache,
+        RenderPipelineDescriptor, TextureFormat, VertexState,
+    },
+    view::ViewTarget,
+    RenderApp, RenderStartup,
+};
+use alloc::borrow::Cow;
+use bevy_app::{App, Plugin};
+use bevy_asset::{embedded_asset, load_embedded_asset, AssetServer, Handle};
+use bevy_ecs::{
+    resource::Resource,
+    system::{Commands, Res},
+};
+use bevy_image::BevyDefault;
+use bevy_platform::collections::HashMap;
+use bevy_shader::Shader;
+use bevy_utils::default;
+use core::hash::Hash;
+
+/// Adds the [`SolidColorFallbackPipeline`].
+///
+/// This plugin isn't added by the [`RenderPlugin`](crate::RenderPlugin), since it compiles
+/// pipelines at startup that most apps never draw with. Add it to the app to use the solid color
+/// fallback.
+pub struct PipelineFallbackPlugin;
+
+impl Plugin for PipelineFallbackPlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "pipeline_fallback.wgsl");
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app.add_systems(RenderStartup, init_solid_color_fallback_pipeline);
+    }
+}
+
+/// Fallback pipelines to use while the pipelines identified by keys of type `K` are being
+/// compiled.
+///
+/// Register a fallback for each key with [`PipelineFallback::insert()`], and pick the pipeline to
+/// draw with using [`PipelineFallback::with_fallback()`]. The resource isn't added automatically,
+/// use `init_resource::<PipelineFallback<K>>()` on the render app for each key type.
+///
+/// The fallback should be queued early, for example in [`RenderStartup`], so that it is ready by
+/// the time the pipelines it stands in for are needed.
+#[derive(Resource)]
+pub struct PipelineFallback<K> {
+    fallbacks: HashMap<K, CachedRenderPipelineId>,
+}
+
+impl<K> Default for PipelineFallback<K> {
+    fn default() -> Self {
+        Self {
+            fallbacks: default(),
+        }
+    }
+}
+
+impl<K: Eq + Hash> PipelineFallback<K> {
+    /// Sets the fallback pipeline for `key`, returning the previous fallback if any.
+    pub fn insert(
+        &mut self,
+        key: K,
+        fallback: CachedRenderPipelineId,
+    ) -> Option<CachedRenderPipelineId> {
+        self.fallbacks.insert(key, fallback)
+    }
+
+    /// Removes the fallback pipeline for `key`.
+    pub fn remove(&mut self, key: &K) -> Option<CachedRenderPipelineId> {
+        self.fallbacks.remove(key)
+    }
+
+    /// Returns the fallback pipeline for `key`.
+    pub fn get(&self, key: &K) -> Option<CachedRenderPipelineId> {
+        self.fallbacks.get(key).copied()
+    }
+
+    /// Returns `primary` if it is ready, or else the fallback pipeline for `key` if that is ready.
+    ///
+    /// Returns `None` if neither pipeline is ready, in which case the draw should be skipped.
+    pub fn with_fallback(
+        &self,
+        pipeline_cache: &PipelineCache,
+        key: &K,
+        primary: CachedRenderPipelineId,
+    ) -> Option<CachedRenderPipelineId> {
+        if pipeline_cache.get_render_pipeline(primary).is_some() {
+            return Some(primary);
+        }
+        self.get(key)
+            .filter(|&fallback| pipeline_cache.get_render_pipeline(fallback).is_some())
+    }
+}
+
+/// A pipeline drawing a fullscreen triangle in a solid color, without any bindings or vertex
+/// buffers, which can be used as a fallback for fullscreen passes.
+///
+/// It is added by the [`PipelineFallbackPlugin`], which queues it at startup for
+/// [`TextureFormat::bevy_default()`] and [`ViewTarget::TEXTURE_FORMAT_HDR`]. Variants for other formats can be queued with
+/// [`SolidColorFallbackPipeline::descriptor()`].
+#[derive(Resource)]
+pub struct SolidColorFallbackPipeline {
+    pub shader: Handle<Shader>,
+    pipelines: HashMap<TextureFormat, CachedRenderPipelineId>,
+}
+
+impl SolidColorFallbackPipeline {
+    /// Returns the pipeline queued at startup for `format`, if any.
+    pub fn get(&self, format: TextureFormat) -> Option<CachedRenderPipelineId> {
+        self.pipelines.get(&format).copied()
+    }
+
+    /// Returns the descriptor of the pipeline for a color target of the given `format`.
+    pub fn descriptor(&self, format: TextureFormat) -> RenderPipelineDescriptor {
+        RenderPipelineDescriptor {
+            label: Some(Cow::Borrowed("solid_color_fallback_pipeline")),
+            vertex: VertexState {
+                shader: self.shader.clone(),
+                ..default()
+            },
+            fragment: Some(FragmentState {
+                shader: self.shader.clone(),
+                targets: vec![Some(ColorTargetState {
+                    format,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+                ..default()
+            }),
+            ..default()
+        }
+    }
+}
+
+fn init_solid_color_fallback_pipeline(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    pipeline_cache: Res<PipelineCache>,
+) {
+    let mut fallback = SolidColorFallbackPipeline {
+        shader: load_embedded_asset!(asset_server.as_ref(), "pipeline_fallback.wgsl"),
+        pipelines: HashMap::default(),
+    };
+    for format in [
+        TextureFormat::bevy_default(),
+        ViewTarget::TEXTURE_FORMAT_HDR,
+    ] {
+        let id = pipeline_cache.queue_render_pipeline(fallback.descriptor(format));
+        fallback.pipelines.insert(format, id);
+    }
+    commands.insert_resource(fallback);
+}
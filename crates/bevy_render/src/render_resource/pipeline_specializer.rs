@@ -11,11 +11,24 @@ use bevy_platform::{
     },
     hash::FixedHasher,
 };
+use bevy_shader::ShaderDefVal;
 use bevy_utils::default;
 use core::{fmt::Debug, hash::Hash};
 use thiserror::Error;
 use tracing::error;
 
+pub use bevy_render_macros::ShaderDefs;
+
+/// A specialization key that can be converted into the shader defs of the pipeline variant it
+/// describes.
+///
+/// This can be derived for structs with `bool`, `u32` and `i32` fields, see
+/// [`derive(ShaderDefs)`](bevy_render_macros::ShaderDefs).
+pub trait ShaderDefs {
+    /// Returns the shader defs for this key.
+    fn shader_defs(&self) -> Vec<ShaderDefVal>;
+}
+
 /// A trait that allows constructing different variants of a render pipeline from a key.
 ///
 /// Note: This is intended for modifying your pipeline descriptor on the basis of a key. If your key
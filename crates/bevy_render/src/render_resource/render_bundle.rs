@@ -0,0 +1,42 @@
+use crate::define_atomic_id;
+use crate::renderer::WgpuWrapper;
+use core::ops::Deref;
+
+define_atomic_id!(RenderBundleId);
+
+/// A [`RenderBundle`] is a pre-recorded sequence of render commands that can be replayed in a
+/// render pass.
+///
+/// May be converted from and dereferences to a wgpu [`RenderBundle`](wgpu::RenderBundle).
+/// Can be created via [`RenderDevice::finish_render_bundle`](crate::renderer::RenderDevice::finish_render_bundle).
+#[derive(Clone, Debug)]
+pub struct RenderBundle {
+    id: RenderBundleId,
+    value: WgpuWrapper<wgpu::RenderBundle>,
+}
+
+impl RenderBundle {
+    /// Returns the [`RenderBundleId`].
+    #[inline]
+    pub fn id(&self) -> RenderBundleId {
+        self.id
+    }
+}
+
+impl From<wgpu::RenderBundle> for RenderBundle {
+    fn from(value: wgpu::RenderBundle) -> Self {
+        RenderBundle {
+            id: RenderBundleId::new(),
+            value: WgpuWrapper::new(value),
+        }
+    }
+}
+
+impl Deref for RenderBundle {
+    type Target = wgpu::RenderBundle;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
@@ -0,0 +1,291 @@
+use crate::render_resource::{
+    BindGroupLayoutDescriptor, CachedPipelineState, ComputePipelineDescriptor, FragmentState,
+    PipelineCache, PipelineDescriptor, RenderPipelineDescriptor, VertexState,
+};
+use alloc::borrow::Cow;
+use bevy_asset::{AssetServer, Handle};
+use bevy_mesh::VertexBufferLayout;
+use bevy_shader::{Shader, ShaderDefVal};
+use core::num::NonZeroU32;
+use serde::{Deserialize, Serialize};
+use wgpu::{
+    BindGroupLayoutEntry, BufferAddress, ColorTargetState, DepthStencilState, MultisampleState,
+    PrimitiveState, PushConstantRange, VertexAttribute, VertexStepMode,
+};
+
+/// A pipeline descriptor in a portable format, with shaders identified by their asset path.
+///
+/// See [`PipelineCache::collect_pipeline_descriptors()`] and
+/// [`PipelineCache::queue_from_serialized_descriptors()`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum SerializedPipelineDescriptor {
+    Render(SerializedRenderPipelineDescriptor),
+    Compute(SerializedComputePipelineDescriptor),
+}
+
+/// A serialized [`RenderPipelineDescriptor`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SerializedRenderPipelineDescriptor {
+    pub label: Option<String>,
+    pub layout: Vec<SerializedBindGroupLayoutDescriptor>,
+    pub push_constant_ranges: Vec<PushConstantRange>,
+    pub vertex: SerializedVertexState,
+    pub primitive: PrimitiveState,
+    pub depth_stencil: Option<DepthStencilState>,
+    pub multisample: MultisampleState,
+    pub fragment: Option<SerializedFragmentState>,
+    pub multiview: Option<NonZeroU32>,
+    pub constants: Vec<(String, f64)>,
+    pub zero_initialize_workgroup_memory: bool,
+}
+
+/// A serialized [`ComputePipelineDescriptor`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SerializedComputePipelineDescriptor {
+    pub label: Option<String>,
+    pub layout: Vec<SerializedBindGroupLayoutDescriptor>,
+    pub push_constant_ranges: Vec<PushConstantRange>,
+    /// The asset path of the shader.
+    pub shader: String,
+    pub shader_defs: Vec<ShaderDefVal>,
+    pub entry_point: Option<String>,
+    pub constants: Vec<(String, f64)>,
+    pub zero_initialize_workgroup_memory: bool,
+}
+
+/// A serialized [`BindGroupLayoutDescriptor`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SerializedBindGroupLayoutDescriptor {
+    pub label: String,
+    pub entries: Vec<BindGroupLayoutEntry>,
+}
+
+/// A serialized [`VertexState`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SerializedVertexState {
+    /// The asset path of the shader.
+    pub shader: String,
+    pub shader_defs: Vec<ShaderDefVal>,
+    pub entry_point: Option<String>,
+    pub buffers: Vec<SerializedVertexBufferLayout>,
+}
+
+/// A serialized [`VertexBufferLayout`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SerializedVertexBufferLayout {
+    pub array_stride: BufferAddress,
+    pub step_mode: VertexStepMode,
+    pub attributes: Vec<VertexAttribute>,
+}
+
+/// A serialized [`FragmentState`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SerializedFragmentState {
+    /// The asset path of the shader.
+    pub shader: String,
+    pub shader_defs: Vec<ShaderDefVal>,
+    pub entry_point: Option<String>,
+    pub targets: Vec<Option<ColorTargetState>>,
+}
+
+impl SerializedPipelineDescriptor {
+    /// Converts a pipeline descriptor, returning `None` if one of its shaders wasn't loaded from an
+    /// asset path.
+    pub fn new(descriptor: &PipelineDescriptor) -> Option<Self> {
+        match descriptor {
+            PipelineDescriptor::RenderPipelineDescriptor(descriptor) => {
+                SerializedRenderPipelineDescriptor::new(descriptor).map(Self::Render)
+            }
+            PipelineDescriptor::ComputePipelineDescriptor(descriptor) => {
+                SerializedComputePipelineDescriptor::new(descriptor).map(Self::Compute)
+            }
+        }
+    }
+}
+
+impl SerializedRenderPipelineDescriptor {
+    /// Converts a render pipeline descriptor, returning `None` if one of its shaders wasn't loaded
+    /// from an asset path.
+    pub fn new(descriptor: &RenderPipelineDescriptor) -> Option<Self> {
+        let fragment = match &descriptor.fragment {
+            Some(fragment) => Some(SerializedFragmentState {
+                shader: shader_path(&fragment.shader)?,
+                shader_defs: fragment.shader_defs.clone(),
+                entry_point: fragment.entry_point.as_deref().map(ToString::to_string),
+                targets: fragment.targets.clone(),
+            }),
+            None => None,
+        };
+        Some(Self {
+            label: descriptor.label.as_deref().map(ToString::to_string),
+            layout: descriptor.layout.iter().map(Into::into).collect(),
+            push_constant_ranges: descriptor.push_constant_ranges.clone(),
+            vertex: SerializedVertexState {
+                shader: shader_path(&descriptor.vertex.shader)?,
+                shader_defs: descriptor.vertex.shader_defs.clone(),
+                entry_point: descriptor
+                    .vertex
+                    .entry_point
+                    .as_deref()
+                    .map(ToString::to_string),
+                buffers: descriptor
+                    .vertex
+                    .buffers
+                    .iter()
+                    .map(|layout| SerializedVertexBufferLayout {
+                        array_stride: layout.array_stride,
+                        step_mode: layout.step_mode,
+                        attributes: layout.attributes.clone(),
+                    })
+                    .collect(),
+            },
+            primitive: descriptor.primitive,
+            depth_stencil: descriptor.depth_stencil.clone(),
+            multisample: descriptor.multisample,
+            fragment,
+            multiview: descriptor.multiview,
+            constants: descriptor.constants.clone(),
+            zero_initialize_workgroup_memory: descriptor.zero_initialize_workgroup_memory,
+        })
+    }
+
+    /// Converts back to a render pipeline descriptor, loading its shaders with `asset_server`.
+    pub fn into_descriptor(self, asset_server: &AssetServer) -> RenderPipelineDescriptor {
+        RenderPipelineDescriptor {
+            label: self.label.map(Cow::Owned),
+            layout: self.layout.into_iter().map(Into::into).collect(),
+            push_constant_ranges: self.push_constant_ranges,
+            vertex: VertexState {
+                shader: asset_server.load(self.vertex.shader),
+                shader_defs: self.vertex.shader_defs,
+                entry_point: self.vertex.entry_point.map(Cow::Owned),
+                buffers: self
+                    .vertex
+                    .buffers
+                    .into_iter()
+                    .map(|layout| VertexBufferLayout {
+                        array_stride: layout.array_stride,
+                        step_mode: layout.step_mode,
+                        attributes: layout.attributes,
+                    })
+                    .collect(),
+            },
+            primitive: self.primitive,
+            depth_stencil: self.depth_stencil,
+            multisample: self.multisample,
+            fragment: self.fragment.map(|fragment| FragmentState {
+                shader: asset_server.load(fragment.shader),
+                shader_defs: fragment.shader_defs,
+                entry_point: fragment.entry_point.map(Cow::Owned),
+                targets: fragment.targets,
+            }),
+            multiview: self.multiview,
+            constants: self.constants,
+            zero_initialize_workgroup_memory: self.zero_initialize_workgroup_memory,
+        }
+    }
+}
+
+impl SerializedComputePipelineDescriptor {
+    /// Converts a compute pipeline descriptor, returning `None` if its shader wasn't loaded from an
+    /// asset path.
+    pub fn new(descriptor: &ComputePipelineDescriptor) -> Option<Self> {
+        Some(Self {
+            label: descriptor.label.as_deref().map(ToString::to_string),
+            layout: descriptor.layout.iter().map(Into::into).collect(),
+            push_constant_ranges: descriptor.push_constant_ranges.clone(),
+            shader: shader_path(&descriptor.shader)?,
+            shader_defs: descriptor.shader_defs.clone(),
+            entry_point: descriptor.entry_point.as_deref().map(ToString::to_string),
+            constants: descriptor.constants.clone(),
+            zero_initialize_workgroup_memory: descriptor.zero_initialize_workgroup_memory,
+        })
+    }
+
+    /// Converts back to a compute pipeline descriptor, loading its shader with `asset_server`.
+    pub fn into_descriptor(self, asset_server: &AssetServer) -> ComputePipelineDescriptor {
+        ComputePipelineDescriptor {
+            label: self.label.map(Cow::Owned),
+            layout: self.layout.into_iter().map(Into::into).collect(),
+            push_constant_ranges: self.push_constant_ranges,
+            shader: asset_server.load(self.shader),
+            shader_defs: self.shader_defs,
+            entry_point: self.entry_point.map(Cow::Owned),
+            constants: self.constants,
+            zero_initialize_workgroup_memory: self.zero_initialize_workgroup_memory,
+        }
+    }
+}
+
+impl From<&BindGroupLayoutDescriptor> for SerializedBindGroupLayoutDescriptor {
+    fn from(descriptor: &BindGroupLayoutDescriptor) -> Self {
+        Self {
+            label: descriptor.label.to_string(),
+            entries: descriptor.entries.clone(),
+        }
+    }
+}
+
+impl From<SerializedBindGroupLayoutDescriptor> for BindGroupLayoutDescriptor {
+    fn from(descriptor: SerializedBindGroupLayoutDescriptor) -> Self {
+        Self {
+            label: Cow::Owned(descriptor.label),
+            entries: descriptor.entries,
+        }
+    }
+}
+
+fn shader_path(shader: &Handle<Shader>) -> Option<String> {
+    Some(shader.path()?.to_string())
+}
+
+impl PipelineCache {
+    /// Returns the descriptors of all pipelines in the cache in a portable format, for example to
+    /// pre-generate the pipelines of an app offline.
+    ///
+    /// Removed pipelines, and pipelines with a shader that wasn't loaded from an asset path, are
+    /// skipped.
+    pub fn collect_pipeline_descriptors(&self) -> Vec<SerializedPipelineDescriptor> {
+        self.pipelines()
+            .filter(|pipeline| !matches!(pipeline.state, CachedPipelineState::Evicted))
+            .filter_map(|pipeline| SerializedPipelineDescriptor::new(&pipeline.descriptor))
+            .collect()
+    }
+
+    /// Queues the creation of pipelines collected with
+    /// [`PipelineCache::collect_pipeline_descriptors()`], loading their shaders with
+    /// `asset_server`.
+    ///
+    /// This is meant to warm up the cache on startup, before the pipelines are first needed.
+    pub fn queue_from_serialized_descriptors(
+        &self,
+        descriptors: Vec<SerializedPipelineDescriptor>,
+        asset_server: &AssetServer,
+    ) {
+        for descriptor in descriptors {
+            match descriptor {
+                SerializedPipelineDescriptor::Render(descriptor) => {
+                    self.queue_render_pipeline(descriptor.into_descriptor(asset_server));
+                }
+                SerializedPipelineDescriptor::Compute(descriptor) => {
+                    self.queue_compute_pipeline(descriptor.into_descriptor(asset_server));
+                }
+            }
+        }
+    }
+
+    /// Serializes the descriptors of all pipelines in the cache to RON, see
+    /// [`PipelineCache::collect_pipeline_descriptors()`].
+    pub fn serialize_to_bytes(&self) -> Result<Vec<u8>, ron::Error> {
+        let descriptors = self.collect_pipeline_descriptors();
+        Ok(ron::ser::to_string(&descriptors)?.into_bytes())
+    }
+
+    /// Deserializes pipeline descriptors written by [`PipelineCache::serialize_to_bytes()`], to be
+    /// queued with [`PipelineCache::queue_from_serialized_descriptors()`].
+    pub fn deserialize_from_bytes(
+        bytes: &[u8],
+    ) -> Result<Vec<SerializedPipelineDescriptor>, ron::error::SpannedError> {
+        ron::de::from_bytes(bytes)
+    }
+}
@@ -134,9 +134,20 @@ pub struct RenderAdapter(pub Arc<WgpuWrapper<Adapter>>);
 pub struct RenderInstance(pub Arc<WgpuWrapper<Instance>>);
 
 /// The [`AdapterInfo`] of the adapter in use by the renderer.
+///
+/// This contains the GPU name, vendor and device IDs and driver version, which are useful in bug
+/// reports.
 #[derive(Resource, Clone, Deref, DerefMut)]
 pub struct RenderAdapterInfo(pub WgpuWrapper<AdapterInfo>);
 
+impl RenderAdapterInfo {
+    /// The graphics API backend used by the renderer.
+    #[inline]
+    pub fn backend(&self) -> wgpu::Backend {
+        self.0.backend
+    }
+}
+
 const GPU_NOT_FOUND_ERROR_MESSAGE: &str = if cfg!(target_os = "linux") {
     "Unable to find a GPU! Make sure you have installed required drivers! For extra information, see: https://github.com/bevyengine/bevy/blob/latest/docs/linux_dependencies.md"
 } else {
@@ -459,11 +470,13 @@ pub async fn initialize_renderer(
     debug!("Configured wgpu adapter Limits: {:#?}", device.limits());
     debug!("Configured wgpu adapter Features: {:#?}", device.features());
 
+    let adapter = RenderAdapter(Arc::new(WgpuWrapper::new(adapter)));
+
     RenderResources(
-        RenderDevice::from(device),
+        RenderDevice::from(device).with_adapter(adapter.clone()),
         RenderQueue(Arc::new(WgpuWrapper::new(queue))),
         RenderAdapterInfo(WgpuWrapper::new(adapter_info)),
-        RenderAdapter(Arc::new(WgpuWrapper::new(adapter))),
+        adapter,
         RenderInstance(Arc::new(WgpuWrapper::new(instance))),
         #[cfg(feature = "raw_vulkan_init")]
         additional_vulkan_features,
@@ -1,10 +1,17 @@
-use super::RenderQueue;
+use super::{RenderAdapter, RenderQueue};
 use crate::render_resource::{
-    BindGroup, BindGroupLayout, Buffer, ComputePipeline, RawRenderPipelineDescriptor,
-    RenderPipeline, Sampler, Texture,
+    binding_types, BindGroup, BindGroupLayout, Blas, BlasGeometrySizeDescriptors, Buffer,
+    BufferPool, ComputePipeline, CreateBlasDescriptor, CreateTlasDescriptor,
+    RawRenderPipelineDescriptor, RenderBundle, RenderPipeline, Sampler, Texture, TextureView, Tlas,
 };
 use crate::renderer::WgpuWrapper;
+use alloc::sync::Arc;
 use bevy_ecs::resource::Resource;
+use bevy_platform::collections::HashMap;
+use core::ops::RangeBounds;
+use encase::ShaderType;
+use std::sync::{mpsc, Mutex, PoisonError};
+use thiserror::Error;
 use wgpu::{
     util::DeviceExt, BindGroupDescriptor, BindGroupEntry, BindGroupLayoutDescriptor,
     BindGroupLayoutEntry, BufferAsyncError, BufferBindingType, PollError, PollStatus,
@@ -14,8 +21,59 @@ use wgpu::{
 #[derive(Resource, Clone)]
 pub struct RenderDevice {
     device: WgpuWrapper<wgpu::Device>,
+    /// The adapter the device was requested from, used to query adapter specific capabilities.
+    adapter: Option<RenderAdapter>,
+    /// The formats of [`STORAGE_TEXTURE_FORMATS`] supporting read-write storage access, see
+    /// [`RenderDevice::supported_storage_texture_formats`].
+    storage_texture_formats: Arc<[wgpu::TextureFormat]>,
+    /// The downlevel capabilities of the adapter, see [`RenderDevice::downlevel_capabilities`].
+    downlevel_capabilities: wgpu::DownlevelCapabilities,
+    /// The GPU objects used by [`RenderDevice::create_texture_with_mipmaps`], created on first use.
+    mipmap_generation: Arc<Mutex<MipmapGeneration>>,
 }
 
+/// The shader, sampler and per-format pipelines used to generate mipmaps, kept so they aren't
+/// created again for every texture.
+#[derive(Default)]
+struct MipmapGeneration {
+    shader: Option<WgpuWrapper<wgpu::ShaderModule>>,
+    sampler: Option<Sampler>,
+    pipelines: HashMap<wgpu::TextureFormat, RenderPipeline>,
+}
+
+/// An error returned by [`RenderDevice::create_texture_with_mipmaps`].
+#[derive(Error, Debug)]
+pub enum MipmapGenerationError {
+    #[error("mipmap generation is only supported for 2D textures, got a {0:?} texture")]
+    UnsupportedDimension(wgpu::TextureDimension),
+    #[error("mipmap generation is not supported for the compressed or depth/stencil format {0:?}")]
+    UnsupportedFormat(wgpu::TextureFormat),
+}
+
+/// The texture formats checked by [`RenderDevice::supported_storage_texture_formats`].
+const STORAGE_TEXTURE_FORMATS: &[wgpu::TextureFormat] = &[
+    wgpu::TextureFormat::R32Uint,
+    wgpu::TextureFormat::R32Sint,
+    wgpu::TextureFormat::R32Float,
+    wgpu::TextureFormat::R64Uint,
+    wgpu::TextureFormat::Rg32Uint,
+    wgpu::TextureFormat::Rg32Sint,
+    wgpu::TextureFormat::Rg32Float,
+    wgpu::TextureFormat::Rgba8Unorm,
+    wgpu::TextureFormat::Rgba8Snorm,
+    wgpu::TextureFormat::Rgba8Uint,
+    wgpu::TextureFormat::Rgba8Sint,
+    wgpu::TextureFormat::Bgra8Unorm,
+    wgpu::TextureFormat::Rgb10a2Unorm,
+    wgpu::TextureFormat::Rg11b10Ufloat,
+    wgpu::TextureFormat::Rgba16Uint,
+    wgpu::TextureFormat::Rgba16Sint,
+    wgpu::TextureFormat::Rgba16Float,
+    wgpu::TextureFormat::Rgba32Uint,
+    wgpu::TextureFormat::Rgba32Sint,
+    wgpu::TextureFormat::Rgba32Float,
+];
+
 impl From<wgpu::Device> for RenderDevice {
     fn from(device: wgpu::Device) -> Self {
         Self::new(WgpuWrapper::new(device))
@@ -24,7 +82,38 @@ impl From<wgpu::Device> for RenderDevice {
 
 impl RenderDevice {
     pub fn new(device: WgpuWrapper<wgpu::Device>) -> Self {
-        Self { device }
+        let mut render_device = Self {
+            device,
+            adapter: None,
+            storage_texture_formats: Arc::new([]),
+            downlevel_capabilities: wgpu::DownlevelCapabilities::default(),
+            mipmap_generation: Arc::default(),
+        };
+        render_device.update_storage_texture_formats();
+        render_device
+    }
+
+    /// Sets the adapter the device was requested from.
+    ///
+    /// Without an adapter, [`RenderDevice::texture_format_features`] can only report the features
+    /// guaranteed by the WebGPU specification.
+    pub fn with_adapter(mut self, adapter: RenderAdapter) -> Self {
+        self.downlevel_capabilities = adapter.0.get_downlevel_capabilities();
+        self.adapter = Some(adapter);
+        self.update_storage_texture_formats();
+        self
+    }
+
+    fn update_storage_texture_formats(&mut self) {
+        self.storage_texture_formats = STORAGE_TEXTURE_FORMATS
+            .iter()
+            .copied()
+            .filter(|&format| {
+                self.texture_format_features(format)
+                    .flags
+                    .contains(wgpu::TextureFormatFeatureFlags::STORAGE_READ_WRITE)
+            })
+            .collect();
     }
 
     /// List all [`Features`](wgpu::Features) that may be used with this device.
@@ -43,6 +132,58 @@ impl RenderDevice {
         self.device.limits()
     }
 
+    /// Returns the [`DownlevelCapabilities`](wgpu::DownlevelCapabilities) of the adapter the device
+    /// was requested from, i.e. which features of WebGPU are missing on downlevel backends such as
+    /// WebGL2.
+    ///
+    /// This is queried once when the adapter is set. If the device was created without an adapter,
+    /// it is assumed to be fully WebGPU compliant.
+    #[inline]
+    pub fn downlevel_capabilities(&self) -> wgpu::DownlevelCapabilities {
+        self.downlevel_capabilities.clone()
+    }
+
+    /// Returns `true` if the adapter the device was requested from supports `flag`, for example
+    /// [`DownlevelFlags::COMPUTE_SHADERS`](wgpu::DownlevelFlags::COMPUTE_SHADERS).
+    ///
+    /// See [`RenderDevice::downlevel_capabilities`].
+    #[inline]
+    pub fn supports_downlevel_flag(&self, flag: wgpu::DownlevelFlags) -> bool {
+        self.downlevel_capabilities.flags.contains(flag)
+    }
+
+    /// Returns the [`TextureFormatFeatures`](wgpu::TextureFormatFeatures) supported by this device
+    /// for the given `format`, such as whether it can be used as a storage texture, filtered or
+    /// rendered to.
+    ///
+    /// If the device was created without an adapter, this returns the features every device is
+    /// guaranteed to support, which may be fewer than the actual capabilities.
+    pub fn texture_format_features(
+        &self,
+        format: wgpu::TextureFormat,
+    ) -> wgpu::TextureFormatFeatures {
+        match &self.adapter {
+            Some(adapter)
+                if self
+                    .features()
+                    .contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES) =>
+            {
+                adapter.0.get_texture_format_features(format)
+            }
+            _ => format.guaranteed_format_features(self.features()),
+        }
+    }
+
+    /// Returns the commonly used texture formats that support read-write storage access on this
+    /// device, i.e. that can be bound as `texture_storage_*d<format, read_write>`.
+    ///
+    /// This is computed once when the device is created. Like
+    /// [`RenderDevice::texture_format_features`], it only reports the guaranteed capabilities if the
+    /// device was created without an adapter.
+    pub fn supported_storage_texture_formats(&self) -> &[wgpu::TextureFormat] {
+        &self.storage_texture_formats
+    }
+
     /// Creates a [`ShaderModule`](wgpu::ShaderModule) from either SPIR-V or WGSL source code.
     ///
     /// # Safety
@@ -125,6 +266,25 @@ impl RenderDevice {
         self.device.poll(maintain)
     }
 
+    /// Polls the device without blocking, see [`RenderDevice::poll()`].
+    ///
+    /// Returns `true` if the queue is empty, and `false` if submissions are still in flight or
+    /// polling failed.
+    #[inline]
+    pub fn poll_device(&self) -> bool {
+        self.poll(wgpu::PollType::Poll)
+            .is_ok_and(|status| status.is_queue_empty())
+    }
+
+    /// Blocks until all submitted work is done, see [`RenderDevice::poll()`].
+    ///
+    /// This is useful to make sure submitted work is complete, for example in tests or when
+    /// capturing screenshots. Errors, such as a lost device, are ignored.
+    #[inline]
+    pub fn poll_device_wait(&self) {
+        let _ = self.poll(wgpu::PollType::Wait);
+    }
+
     /// Creates an empty [`CommandEncoder`](wgpu::CommandEncoder).
     #[inline]
     pub fn create_command_encoder(
@@ -143,6 +303,17 @@ impl RenderDevice {
         self.device.create_render_bundle_encoder(desc)
     }
 
+    /// Finishes recording a [`RenderBundleEncoder`](wgpu::RenderBundleEncoder) created with
+    /// [`RenderDevice::create_render_bundle_encoder`] into a [`RenderBundle`].
+    #[inline]
+    pub fn finish_render_bundle(
+        &self,
+        encoder: wgpu::RenderBundleEncoder,
+        label: Option<&str>,
+    ) -> RenderBundle {
+        RenderBundle::from(encoder.finish(&wgpu::RenderBundleDescriptor { label }))
+    }
+
     /// Creates a new [`BindGroup`](wgpu::BindGroup).
     #[inline]
     pub fn create_bind_group<'a>(
@@ -159,6 +330,34 @@ impl RenderDevice {
         BindGroup::from(wgpu_bind_group)
     }
 
+    /// Creates a new [`BindGroup`](wgpu::BindGroup) binding each of `resources` to the binding
+    /// matching its index in the slice.
+    ///
+    /// This is a shorthand for [`RenderDevice::create_bind_group`] with entries binding
+    /// `resources[0]` at binding 0, `resources[1]` at binding 1, and so on. See also
+    /// [`BindGroupEntries::sequential`](crate::render_resource::BindGroupEntries::sequential).
+    pub fn create_bind_group_auto<'a>(
+        &self,
+        label: impl Into<wgpu::Label<'a>>,
+        layout: &'a BindGroupLayout,
+        resources: &'a [wgpu::BindingResource<'a>],
+    ) -> BindGroup {
+        let entries = resources
+            .iter()
+            .enumerate()
+            .map(|(binding, resource)| BindGroupEntry {
+                binding: binding as u32,
+                resource: resource.clone(),
+            })
+            .collect::<Vec<_>>();
+        let wgpu_bind_group = self.device.create_bind_group(&BindGroupDescriptor {
+            label: label.into(),
+            layout,
+            entries: &entries,
+        });
+        BindGroup::from(wgpu_bind_group)
+    }
+
     /// Creates a [`BindGroupLayout`](wgpu::BindGroupLayout).
     #[inline]
     pub fn create_bind_group_layout<'a>(
@@ -175,6 +374,45 @@ impl RenderDevice {
         )
     }
 
+    /// Creates a [`BindGroupLayout`] with a single uniform buffer binding of type `T`.
+    ///
+    /// The minimum binding size is the size of `T` in WGSL, see [`ShaderType::min_size`].
+    pub fn create_bind_group_layout_for_uniform_buffer<T: ShaderType>(
+        &self,
+        label: Option<&str>,
+        binding: u32,
+        visibility: wgpu::ShaderStages,
+    ) -> BindGroupLayout {
+        let entry = binding_types::uniform_buffer::<T>(false).build(binding, visibility);
+        self.create_bind_group_layout(label, &[entry])
+    }
+
+    /// Creates a [`BindGroupLayout`] with a single read-only storage buffer binding of type `T`.
+    ///
+    /// The minimum binding size is the size of `T` in WGSL, see [`ShaderType::min_size`].
+    pub fn create_bind_group_layout_for_storage_buffer_ro<T: ShaderType>(
+        &self,
+        label: Option<&str>,
+        binding: u32,
+        visibility: wgpu::ShaderStages,
+    ) -> BindGroupLayout {
+        let entry = binding_types::storage_buffer_read_only::<T>(false).build(binding, visibility);
+        self.create_bind_group_layout(label, &[entry])
+    }
+
+    /// Creates a [`BindGroupLayout`] with a single read-write storage buffer binding of type `T`.
+    ///
+    /// The minimum binding size is the size of `T` in WGSL, see [`ShaderType::min_size`].
+    pub fn create_bind_group_layout_for_storage_buffer_rw<T: ShaderType>(
+        &self,
+        label: Option<&str>,
+        binding: u32,
+        visibility: wgpu::ShaderStages,
+    ) -> BindGroupLayout {
+        let entry = binding_types::storage_buffer::<T>(false).build(binding, visibility);
+        self.create_bind_group_layout(label, &[entry])
+    }
+
     /// Creates a [`PipelineLayout`](wgpu::PipelineLayout).
     #[inline]
     pub fn create_pipeline_layout(
@@ -207,12 +445,77 @@ impl RenderDevice {
         Buffer::from(wgpu_buffer)
     }
 
+    /// Creates a [`BufferPool`] of transient buffers, which destroys buffers that weren't used
+    /// for `max_unused_frames` frames.
+    pub fn create_buffer_pool(&self, max_unused_frames: u64) -> BufferPool {
+        BufferPool::new(self.clone(), max_unused_frames)
+    }
+
     /// Creates a [`Buffer`] and initializes it with the specified data.
     pub fn create_buffer_with_data(&self, desc: &wgpu::util::BufferInitDescriptor) -> Buffer {
         let wgpu_buffer = self.device.create_buffer_init(desc);
         Buffer::from(wgpu_buffer)
     }
 
+    /// Creates a [`Buffer`] that can be copied into and mapped for reading on the CPU.
+    pub fn create_staging_buffer(&self, size: u64, label: Option<&str>) -> Buffer {
+        self.create_buffer(&wgpu::BufferDescriptor {
+            label,
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Creates a staging [`Buffer`] large enough to receive a copy of the first mip level of
+    /// `texture`, with each row padded to [`wgpu::COPY_BYTES_PER_ROW_ALIGNMENT`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `texture` has a combined depth-stencil format, which must be copied one aspect
+    /// at a time.
+    pub fn create_staging_buffer_for_texture(&self, texture: &Texture) -> Buffer {
+        let format = texture.format();
+        let (block_width, block_height) = format.block_dimensions();
+        let block_size = format
+            .block_copy_size(None)
+            .expect("combined depth-stencil textures must be copied one aspect at a time");
+        let bytes_per_row = Self::align_copy_bytes_per_row(
+            (texture.width().div_ceil(block_width) * block_size) as usize,
+        );
+        let rows = texture.height().div_ceil(block_height) * texture.depth_or_array_layers();
+        self.create_staging_buffer(bytes_per_row as u64 * rows as u64, None)
+    }
+
+    /// Creates a [`Buffer`] large enough to hold `count` values of type `T`.
+    pub fn create_buffer_typed<T: bytemuck::Pod>(
+        &self,
+        count: usize,
+        usage: wgpu::BufferUsages,
+        mapped_at_creation: bool,
+    ) -> Buffer {
+        self.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: (size_of::<T>() * count) as u64,
+            usage,
+            mapped_at_creation,
+        })
+    }
+
+    /// Creates a [`Buffer`] and initializes it with the specified values.
+    pub fn create_buffer_with_data_typed<T: bytemuck::Pod>(
+        &self,
+        label: Option<&str>,
+        data: &[T],
+        usage: wgpu::BufferUsages,
+    ) -> Buffer {
+        self.create_buffer_with_data(&wgpu::util::BufferInitDescriptor {
+            label,
+            contents: bytemuck::cast_slice(data),
+            usage,
+        })
+    }
+
     /// Creates a new [`Texture`] and initializes it with the specified data.
     ///
     /// `desc` specifies the general format of the texture.
@@ -230,6 +533,182 @@ impl RenderDevice {
         Texture::from(wgpu_texture)
     }
 
+    /// Creates a new [`Texture`], initializes its first mip level with the specified data and
+    /// generates the rest of its mip chain on the GPU.
+    ///
+    /// The texture has `mip_level_count` mip levels, or the full mip chain down to 1x1 if it is
+    /// `None`, regardless of `desc.mip_level_count`. The `RENDER_ATTACHMENT` and
+    /// `TEXTURE_BINDING` usages are added automatically, as each level is produced by a linearly
+    /// filtered blit from the previous one. The pipeline doing the blit is created once per
+    /// texture format and reused.
+    ///
+    /// `data` only contains the texels of mip level 0, for every array layer.
+    ///
+    /// Returns an error if `desc` isn't a 2D texture with an uncompressed color format. The format
+    /// must also be filterable and renderable.
+    pub fn create_texture_with_mipmaps(
+        &self,
+        render_queue: &RenderQueue,
+        desc: &wgpu::TextureDescriptor,
+        mip_level_count: Option<u32>,
+        data: &[u8],
+    ) -> Result<Texture, MipmapGenerationError> {
+        if desc.dimension != wgpu::TextureDimension::D2 {
+            return Err(MipmapGenerationError::UnsupportedDimension(desc.dimension));
+        }
+        let block_size = desc
+            .format
+            .block_copy_size(None)
+            .ok_or(MipmapGenerationError::UnsupportedFormat(desc.format))?;
+
+        let mip_level_count = mip_level_count.unwrap_or_else(|| desc.size.max_mips(desc.dimension));
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            mip_level_count,
+            usage: desc.usage
+                | wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_DST,
+            ..*desc
+        });
+
+        let queue: &wgpu::Queue = render_queue.as_ref();
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            data,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(desc.size.width * block_size),
+                rows_per_image: Some(desc.size.height),
+            },
+            desc.size,
+        );
+
+        let (pipeline, sampler) = self.mipmap_generation_pipeline(desc.format);
+        let bind_group_layout = pipeline.get_bind_group_layout(0);
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("mipmap_generation"),
+            });
+        for layer in 0..desc.size.depth_or_array_layers {
+            let mip_view = |mip_level| {
+                texture.create_view(&wgpu::TextureViewDescriptor {
+                    dimension: Some(wgpu::TextureViewDimension::D2),
+                    base_mip_level: mip_level,
+                    mip_level_count: Some(1),
+                    base_array_layer: layer,
+                    array_layer_count: Some(1),
+                    ..Default::default()
+                })
+            };
+            for mip_level in 1..mip_level_count {
+                let source = mip_view(mip_level - 1);
+                let destination = mip_view(mip_level);
+                let bind_group = self.device.create_bind_group(&BindGroupDescriptor {
+                    label: Some("mipmap_generation_bind_group"),
+                    layout: &bind_group_layout,
+                    entries: &[
+                        BindGroupEntry {
+                            binding: 0,
+                            resource: wgpu::BindingResource::TextureView(&source),
+                        },
+                        BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::Sampler(&sampler),
+                        },
+                    ],
+                });
+
+                let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("mipmap_generation_pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: &destination,
+                        depth_slice: None,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+                pass.set_pipeline(&pipeline);
+                pass.set_bind_group(0, &bind_group, &[]);
+                pass.draw(0..3, 0..1);
+            }
+        }
+        queue.submit([encoder.finish()]);
+
+        Ok(Texture::from(texture))
+    }
+
+    /// Returns the pipeline generating mipmaps for `format` and its sampler, creating them if
+    /// this is the first time they are needed.
+    fn mipmap_generation_pipeline(&self, format: wgpu::TextureFormat) -> (RenderPipeline, Sampler) {
+        let mut mipmap_generation = self
+            .mipmap_generation
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let MipmapGeneration {
+            shader,
+            sampler,
+            pipelines,
+        } = &mut *mipmap_generation;
+        let shader = &*shader.get_or_insert_with(|| {
+            WgpuWrapper::new(
+                self.device
+                    .create_shader_module(wgpu::ShaderModuleDescriptor {
+                        label: Some("mipmap_generation_shader"),
+                        source: wgpu::ShaderSource::Wgsl(
+                            include_str!("mipmap_generation.wgsl").into(),
+                        ),
+                    }),
+            )
+        });
+        let pipeline = pipelines.entry(format).or_insert_with(|| {
+            RenderPipeline::from(self.device.create_render_pipeline(
+                &wgpu::RenderPipelineDescriptor {
+                    label: Some("mipmap_generation_pipeline"),
+                    layout: None,
+                    vertex: wgpu::VertexState {
+                        module: shader,
+                        entry_point: Some("vertex"),
+                        compilation_options: Default::default(),
+                        buffers: &[],
+                    },
+                    primitive: wgpu::PrimitiveState::default(),
+                    depth_stencil: None,
+                    multisample: wgpu::MultisampleState::default(),
+                    fragment: Some(wgpu::FragmentState {
+                        module: shader,
+                        entry_point: Some("fragment"),
+                        compilation_options: Default::default(),
+                        targets: &[Some(format.into())],
+                    }),
+                    multiview: None,
+                    cache: None,
+                },
+            ))
+        });
+        let sampler = sampler.get_or_insert_with(|| {
+            self.create_sampler(&wgpu::SamplerDescriptor {
+                label: Some("mipmap_generation_sampler"),
+                mag_filter: wgpu::FilterMode::Linear,
+                min_filter: wgpu::FilterMode::Linear,
+                ..Default::default()
+            })
+        });
+        (pipeline.clone(), sampler.clone())
+    }
+
     /// Creates a new [`Texture`].
     ///
     /// `desc` specifies the general format of the texture.
@@ -238,6 +717,111 @@ impl RenderDevice {
         Texture::from(wgpu_texture)
     }
 
+    /// Creates a new [`Texture`] and a [`TextureView`] of the whole texture.
+    ///
+    /// `desc` specifies the general format of the texture.
+    pub fn create_texture_and_view(
+        &self,
+        desc: &wgpu::TextureDescriptor,
+    ) -> (Texture, TextureView) {
+        let texture = self.create_texture(desc);
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        (texture, view)
+    }
+
+    /// Creates a new [`Texture`] initialized with the specified data, and a [`TextureView`] of
+    /// the whole texture.
+    ///
+    /// See [`RenderDevice::create_texture_with_data`].
+    pub fn create_texture_with_data_and_view(
+        &self,
+        render_queue: &RenderQueue,
+        desc: &wgpu::TextureDescriptor,
+        order: wgpu::util::TextureDataOrder,
+        data: &[u8],
+    ) -> (Texture, TextureView) {
+        let texture = self.create_texture_with_data(render_queue, desc, order, data);
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        (texture, view)
+    }
+
+    /// Creates a 2D [`TextureView`] of a single array layer of `texture`.
+    pub fn create_texture_view_2d(&self, texture: &Texture, array_layer: u32) -> TextureView {
+        texture.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(wgpu::TextureViewDimension::D2),
+            base_array_layer: array_layer,
+            array_layer_count: Some(1),
+            ..Default::default()
+        })
+    }
+
+    /// Creates a cube [`TextureView`] of the first six array layers of `texture`.
+    pub fn create_texture_view_cube(&self, texture: &Texture) -> TextureView {
+        texture.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(wgpu::TextureViewDimension::Cube),
+            array_layer_count: Some(6),
+            ..Default::default()
+        })
+    }
+
+    /// Creates a new cubemap [`Texture`] with six square faces of `size` texels, and a cube
+    /// [`TextureView`] of it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` isn't a power of two.
+    pub fn create_texture_cubemap(
+        &self,
+        size: u32,
+        format: wgpu::TextureFormat,
+        usage: wgpu::TextureUsages,
+        label: Option<&str>,
+    ) -> (Texture, TextureView) {
+        let texture = self.create_texture(&cubemap_descriptor(size, format, usage, label));
+        let view = self.create_texture_view_cube(&texture);
+        (texture, view)
+    }
+
+    /// Creates a new cubemap [`Texture`] initialized with the data of its six faces, and a cube
+    /// [`TextureView`] of it.
+    ///
+    /// The faces are given in `[+X, -X, +Y, -Y, +Z, -Z]` order, the order of the array layers of
+    /// a cubemap. See [`RenderDevice::create_texture_cubemap`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` isn't a power of two, or if the faces don't all have the same length.
+    pub fn create_texture_cubemap_with_data(
+        &self,
+        render_queue: &RenderQueue,
+        size: u32,
+        format: wgpu::TextureFormat,
+        usage: wgpu::TextureUsages,
+        faces: [&[u8]; 6],
+        label: Option<&str>,
+    ) -> (Texture, TextureView) {
+        assert!(
+            faces.iter().all(|face| face.len() == faces[0].len()),
+            "cubemap faces must all have the same length"
+        );
+        let texture = self.create_texture_with_data(
+            render_queue,
+            &cubemap_descriptor(size, format, usage, label),
+            wgpu::util::TextureDataOrder::LayerMajor,
+            &faces.concat(),
+        );
+        let view = self.create_texture_view_cube(&texture);
+        (texture, view)
+    }
+
+    /// Creates a 2D array [`TextureView`] of all array layers of `texture`.
+    pub fn create_texture_view_array(&self, texture: &Texture) -> TextureView {
+        texture.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(wgpu::TextureViewDimension::D2Array),
+            ..Default::default()
+        })
+    }
+
     /// Creates a new [`Sampler`].
     ///
     /// `desc` specifies the behavior of the sampler.
@@ -246,6 +830,63 @@ impl RenderDevice {
         Sampler::from(wgpu_sampler)
     }
 
+    /// Creates a new bottom level acceleration structure ([`Blas`]) for ray tracing.
+    ///
+    /// `sizes` describes the geometry the acceleration structure will be built from.
+    /// Requires the [`EXPERIMENTAL_RAY_QUERY`](wgpu::Features::EXPERIMENTAL_RAY_QUERY) feature.
+    pub fn create_blas(
+        &self,
+        desc: &CreateBlasDescriptor,
+        sizes: BlasGeometrySizeDescriptors,
+    ) -> Blas {
+        self.device.create_blas(desc, sizes)
+    }
+
+    /// Creates a new top level acceleration structure ([`Tlas`]) for ray tracing.
+    ///
+    /// Requires the [`EXPERIMENTAL_RAY_QUERY`](wgpu::Features::EXPERIMENTAL_RAY_QUERY) feature.
+    pub fn create_tlas(&self, desc: &CreateTlasDescriptor) -> Tlas {
+        self.device.create_tlas(desc)
+    }
+
+    /// Creates a [`QuerySet`](wgpu::QuerySet).
+    #[inline]
+    pub fn create_query_set(&self, desc: &wgpu::QuerySetDescriptor) -> wgpu::QuerySet {
+        self.device.create_query_set(desc)
+    }
+
+    /// Creates a [`QuerySet`](wgpu::QuerySet) of `count` timestamp queries.
+    ///
+    /// Requires [`Features::TIMESTAMP_QUERY`](wgpu::Features::TIMESTAMP_QUERY).
+    pub fn create_timestamp_query_set(&self, count: u32) -> wgpu::QuerySet {
+        self.create_query_set(&wgpu::QuerySetDescriptor {
+            label: None,
+            ty: wgpu::QueryType::Timestamp,
+            count,
+        })
+    }
+
+    /// Creates a [`QuerySet`](wgpu::QuerySet) of `count` pipeline statistics queries, counting
+    /// all [`PipelineStatisticsTypes`](wgpu::PipelineStatisticsTypes).
+    ///
+    /// Requires [`Features::PIPELINE_STATISTICS_QUERY`](wgpu::Features::PIPELINE_STATISTICS_QUERY).
+    pub fn create_pipeline_statistics_query_set(&self, count: u32) -> wgpu::QuerySet {
+        self.create_query_set(&wgpu::QuerySetDescriptor {
+            label: None,
+            ty: wgpu::QueryType::PipelineStatistics(wgpu::PipelineStatisticsTypes::all()),
+            count,
+        })
+    }
+
+    /// Creates a [`QuerySet`](wgpu::QuerySet) of `count` occlusion queries.
+    pub fn create_occlusion_query_set(&self, count: u32) -> wgpu::QuerySet {
+        self.create_query_set(&wgpu::QuerySetDescriptor {
+            label: None,
+            ty: wgpu::QueryType::Occlusion,
+            count,
+        })
+    }
+
     /// Initializes [`Surface`](wgpu::Surface) for presentation.
     ///
     /// # Panics
@@ -256,6 +897,52 @@ impl RenderDevice {
         surface.configure(&self.device, config);
     }
 
+    /// Initializes [`Surface`](wgpu::Surface) for presentation with a configuration suited to most
+    /// windows, and returns the format it was configured with.
+    ///
+    /// An sRGB format is preferred, falling back to the first format supported by the surface.
+    /// The present mode is [`AutoVsync`](wgpu::PresentMode::AutoVsync) if `vsync` is `true`, and
+    /// [`AutoNoVsync`](wgpu::PresentMode::AutoNoVsync) otherwise.
+    ///
+    /// # Panics
+    ///
+    /// - The surface isn't supported by `adapter`.
+    /// - A old [`SurfaceTexture`](wgpu::SurfaceTexture) is still alive referencing an old surface.
+    pub fn configure_surface_optimal(
+        &self,
+        surface: &wgpu::Surface,
+        adapter: &wgpu::Adapter,
+        width: u32,
+        height: u32,
+        vsync: bool,
+    ) -> wgpu::TextureFormat {
+        let caps = surface.get_capabilities(adapter);
+        let format =
+            preferred_surface_format(&caps.formats).expect("No supported formats for surface");
+        self.configure_surface(
+            surface,
+            &wgpu::SurfaceConfiguration {
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                format,
+                width,
+                height,
+                present_mode: if vsync {
+                    wgpu::PresentMode::AutoVsync
+                } else {
+                    wgpu::PresentMode::AutoNoVsync
+                },
+                desired_maximum_frame_latency: 2,
+                alpha_mode: wgpu::CompositeAlphaMode::Auto,
+                view_formats: if !format.is_srgb() {
+                    vec![format.add_srgb_suffix()]
+                } else {
+                    vec![]
+                },
+            },
+        );
+        format
+    }
+
     /// Returns the wgpu [`Device`](wgpu::Device).
     pub fn wgpu_device(&self) -> &wgpu::Device {
         &self.device
@@ -270,16 +957,51 @@ impl RenderDevice {
         buffer.map_async(map_mode, callback);
     }
 
+    /// Maps a range of `buffer` and blocks the calling thread until the mapping is done, returning
+    /// a view of the mapped data.
+    ///
+    /// This waits for all work submitted to the GPU to finish, stalling the render pipeline. Only
+    /// use it in non-real-time contexts, such as tools or tests. Unmap the buffer once the view
+    /// was dropped.
+    pub fn map_buffer_blocking<'a>(
+        &self,
+        buffer: &'a Buffer,
+        range: impl RangeBounds<wgpu::BufferAddress>,
+        map_mode: wgpu::MapMode,
+    ) -> Result<wgpu::BufferView<'a>, BufferAsyncError> {
+        let slice = buffer.slice(range);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(map_mode, move |result| {
+            // The receiver is only dropped once the mapping is done.
+            let _ = sender.send(result);
+        });
+        let result = loop {
+            // If the device was lost, the callback is still called with an error.
+            self.poll_device_wait();
+            if let Ok(result) = receiver.try_recv() {
+                break result;
+            }
+        };
+        result.map(|()| slice.get_mapped_range())
+    }
+
     // Rounds up `row_bytes` to be a multiple of [`wgpu::COPY_BYTES_PER_ROW_ALIGNMENT`].
     pub const fn align_copy_bytes_per_row(row_bytes: usize) -> usize {
-        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as usize;
+        align_to(row_bytes, wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as usize)
+    }
 
-        // If row_bytes is aligned calculate a value just under the next aligned value.
-        // Otherwise calculate a value greater than the next aligned value.
-        let over_aligned = row_bytes + align - 1;
+    /// Rounds up `offset` to be a multiple of the device's
+    /// [`min_uniform_buffer_offset_alignment`](wgpu::Limits::min_uniform_buffer_offset_alignment),
+    /// as required for the offsets of uniform buffer bindings.
+    pub fn align_uniform_buffer_offset(&self, offset: u64) -> u64 {
+        align_buffer_offset(offset, self.limits().min_uniform_buffer_offset_alignment)
+    }
 
-        // Round the number *down* to the nearest aligned value.
-        (over_aligned / align) * align
+    /// Rounds up `offset` to be a multiple of the device's
+    /// [`min_storage_buffer_offset_alignment`](wgpu::Limits::min_storage_buffer_offset_alignment),
+    /// as required for the offsets of storage buffer bindings.
+    pub fn align_storage_buffer_offset(&self, offset: u64) -> u64 {
+        align_buffer_offset(offset, self.limits().min_storage_buffer_offset_alignment)
     }
 
     pub fn get_supported_read_only_binding_type(
@@ -294,6 +1016,68 @@ impl RenderDevice {
     }
 }
 
+/// Picks the format to configure a surface with among the `formats` it supports.
+///
+/// sRGB formats are preferred, falling back to the first available format if there is none.
+pub(crate) fn preferred_surface_format(
+    formats: &[wgpu::TextureFormat],
+) -> Option<wgpu::TextureFormat> {
+    // Rgba8UnormSrgb and Bgra8UnormSrgb and the only sRGB formats wgpu exposes that we can use for surfaces.
+    formats
+        .iter()
+        .find(|format| {
+            matches!(
+                format,
+                wgpu::TextureFormat::Rgba8UnormSrgb | wgpu::TextureFormat::Bgra8UnormSrgb
+            )
+        })
+        .or(formats.first())
+        .copied()
+}
+
+/// The descriptor of a cubemap texture with a single mip level.
+fn cubemap_descriptor<'a>(
+    size: u32,
+    format: wgpu::TextureFormat,
+    usage: wgpu::TextureUsages,
+    label: Option<&'a str>,
+) -> wgpu::TextureDescriptor<'a> {
+    assert!(
+        size.is_power_of_two(),
+        "cubemap size must be a power of two, got {size}"
+    );
+    wgpu::TextureDescriptor {
+        label,
+        size: wgpu::Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 6,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage,
+        view_formats: &[],
+    }
+}
+
+/// Rounds up `value` to be a multiple of `alignment`.
+pub const fn align_to(value: usize, alignment: usize) -> usize {
+    // If value is aligned calculate a value just under the next aligned value.
+    // Otherwise calculate a value greater than the next aligned value.
+    let over_aligned = value + alignment - 1;
+
+    // Round the number *down* to the nearest aligned value.
+    (over_aligned / alignment) * alignment
+}
+
+/// Rounds up `offset` to be a multiple of one of the buffer offset alignments of
+/// [`wgpu::Limits`].
+fn align_buffer_offset(offset: u64, alignment: u32) -> u64 {
+    offset.next_multiple_of(alignment as u64)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -308,4 +1092,27 @@ mod tests {
         assert_eq!(RenderDevice::align_copy_bytes_per_row(align + 1), align * 2);
         assert_eq!(RenderDevice::align_copy_bytes_per_row(align), align);
     }
+
+    #[test]
+    fn align_to_non_power_of_two() {
+        assert_eq!(align_to(0, 3), 0);
+        assert_eq!(align_to(1, 3), 3);
+        assert_eq!(align_to(6, 3), 6);
+        assert_eq!(align_to(7, 3), 9);
+    }
+
+    #[test]
+    fn align_buffer_offsets() {
+        let limits = wgpu::Limits::default();
+        for alignment in [
+            limits.min_uniform_buffer_offset_alignment,
+            limits.min_storage_buffer_offset_alignment,
+        ] {
+            let align = alignment as u64;
+            assert_eq!(align_buffer_offset(0, alignment), 0);
+            assert_eq!(align_buffer_offset(1, alignment), align);
+            assert_eq!(align_buffer_offset(align, alignment), align);
+            assert_eq!(align_buffer_offset(align + 1, alignment), align * 2);
+        }
+    }
 }
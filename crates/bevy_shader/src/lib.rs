@@ -17,6 +17,7 @@ pub mod prelude {
 
 #[doc(hidden)]
 pub mod _macro {
+    pub use alloc::vec::Vec;
     pub use bevy_asset;
 }
 
@@ -37,3 +38,29 @@ macro_rules! load_shader_library {
         core::mem::forget(handle);
     }
 }
+
+/// Creates a `Vec<ShaderDefVal>` with a shader def for each listed cargo feature that is enabled.
+///
+/// The features are checked with `cfg!` in the crate invoking the macro, so plugins can reflect
+/// their own cargo features in their shaders without a `#[cfg]` for each of them.
+///
+/// ```
+/// # use bevy_shader::{shader_defs_from_features, ShaderDefVal};
+/// let shader_defs: Vec<ShaderDefVal> = shader_defs_from_features![
+///     "shader_format_glsl" => "GLSL_SUPPORT",
+///     "shader_format_wesl" => "WESL_SUPPORT",
+/// ];
+/// ```
+#[macro_export]
+macro_rules! shader_defs_from_features {
+    ($($feature: tt => $shader_def: expr),* $(,)?) => {{
+        #[allow(unused_mut, reason = "no shader def is added if no feature is listed")]
+        let mut shader_defs = $crate::_macro::Vec::<$crate::ShaderDefVal>::new();
+        $(
+            if cfg!(feature = $feature) {
+                shader_defs.push($crate::ShaderDefVal::from($shader_def));
+            }
+        )*
+        shader_defs
+    }};
+}
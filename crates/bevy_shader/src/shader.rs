@@ -2,7 +2,12 @@ use super::ShaderDefVal;
 use alloc::borrow::Cow;
 use bevy_asset::{io::Reader, Asset, AssetLoader, AssetPath, Handle, LoadContext};
 use bevy_reflect::TypePath;
-use core::{marker::Copy, num::NonZero};
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+    marker::Copy,
+    num::NonZero,
+};
 use thiserror::Error;
 
 #[derive(Copy, Clone, Hash, Eq, PartialEq, PartialOrd, Ord, Debug)]
@@ -88,6 +93,9 @@ pub struct Shader {
     ///
     /// Please read the [`ValidateShader`] docs for a discussion of the tradeoffs involved.
     pub validate_shader: ValidateShader,
+    /// The label of the shader modules created from this shader, shown in GPU debuggers such as
+    /// RenderDoc. If `None`, the label is derived from the path or import path of the shader.
+    pub label: Option<Cow<'static, str>>,
 }
 
 impl Shader {
@@ -95,7 +103,7 @@ impl Shader {
         let (import_path, imports, _) = naga_oil::compose::get_preprocessor_data(source);
 
         let import_path = import_path
-            .map(ShaderImport::Custom)
+            .map(|import_path| ShaderImport::Custom(import_path.into()))
             .unwrap_or_else(|| ShaderImport::AssetPath(path.to_owned()));
 
         let imports = imports
@@ -110,7 +118,7 @@ impl Shader {
                         .collect();
                     ShaderImport::AssetPath(import)
                 } else {
-                    ShaderImport::Custom(import.import)
+                    ShaderImport::Custom(import.import.into())
                 }
             })
             .collect();
@@ -131,6 +139,7 @@ impl Shader {
             shader_defs: Default::default(),
             file_dependencies: Default::default(),
             validate_shader: ValidateShader::Disabled,
+            label: None,
         }
     }
 
@@ -162,6 +171,7 @@ impl Shader {
             shader_defs: Default::default(),
             file_dependencies: Default::default(),
             validate_shader: ValidateShader::Disabled,
+            label: None,
         }
     }
 
@@ -176,6 +186,7 @@ impl Shader {
             shader_defs: Default::default(),
             file_dependencies: Default::default(),
             validate_shader: ValidateShader::Disabled,
+            label: None,
         }
     }
 
@@ -207,6 +218,7 @@ impl Shader {
                     shader_defs: Default::default(),
                     file_dependencies: Default::default(),
                     validate_shader: ValidateShader::Disabled,
+                    label: None,
                 }
             }
             ShaderImport::Custom(_) => {
@@ -215,16 +227,23 @@ impl Shader {
         }
     }
 
-    pub fn set_import_path<P: Into<String>>(&mut self, import_path: P) {
+    pub fn set_import_path<P: Into<CustomShaderImport>>(&mut self, import_path: P) {
         self.import_path = ShaderImport::Custom(import_path.into());
     }
 
     #[must_use]
-    pub fn with_import_path<P: Into<String>>(mut self, import_path: P) -> Self {
+    pub fn with_import_path<P: Into<CustomShaderImport>>(mut self, import_path: P) -> Self {
         self.set_import_path(import_path);
         self
     }
 
+    /// Sets the label of the shader modules created from this shader, see [`Shader::label`].
+    #[must_use]
+    pub fn with_label(mut self, label: impl Into<Cow<'static, str>>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
     #[inline]
     pub fn import_path(&self) -> &ShaderImport {
         &self.import_path
@@ -250,6 +269,9 @@ impl<'a> From<&'a Shader> for naga_oil::compose::ComposableModuleDescriptor<'a>
                 ShaderDefVal::UInt(name, i) => {
                     (name.clone(), naga_oil::compose::ShaderDefValue::UInt(*i))
                 }
+                ShaderDefVal::Float(name, _) => {
+                    (name.clone(), naga_oil::compose::ShaderDefValue::Bool(true))
+                }
             })
             .collect();
 
@@ -422,18 +444,85 @@ impl AssetLoader for ShaderLoader {
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub enum ShaderImport {
     AssetPath(String),
-    Custom(String),
+    Custom(CustomShaderImport),
 }
 
 impl ShaderImport {
     pub fn module_name(&self) -> Cow<'_, String> {
         match self {
             ShaderImport::AssetPath(s) => Cow::Owned(format!("\"{s}\"")),
-            ShaderImport::Custom(s) => Cow::Borrowed(s),
+            ShaderImport::Custom(custom) => custom.module_name(),
+        }
+    }
+}
+
+/// The name of a [`ShaderImport::Custom`] module.
+///
+/// Two custom imports are equal if they resolve to the same module name, so
+/// `Name("bevy_pbr::mesh_functions")` and `ModulePath(["bevy_pbr", "mesh_functions"])` refer to
+/// the same module.
+#[derive(Debug, Clone)]
+pub enum CustomShaderImport {
+    /// A module name as written in a `#define_import_path` or `#import` directive,
+    /// e.g. `"bevy_pbr::mesh_functions"`.
+    Name(String),
+    /// A module name given as its individual path components,
+    /// e.g. `["bevy_pbr", "mesh_functions"]`.
+    ///
+    /// The components are joined with `::`, not `.`, as that is the separator `naga_oil` uses in
+    /// `#define_import_path` and `#import` directives. This way the module path refers to the same
+    /// module as the name shaders import it by, and is displayed as such in errors.
+    ModulePath(Vec<String>),
+}
+
+impl CustomShaderImport {
+    /// The name `naga_oil` knows this module by, with path components joined by `::`.
+    pub fn module_name(&self) -> Cow<'_, String> {
+        match self {
+            CustomShaderImport::Name(name) => Cow::Borrowed(name),
+            CustomShaderImport::ModulePath(components) => Cow::Owned(components.join("::")),
         }
     }
 }
 
+impl PartialEq for CustomShaderImport {
+    fn eq(&self, other: &Self) -> bool {
+        self.module_name() == other.module_name()
+    }
+}
+
+impl Eq for CustomShaderImport {}
+
+impl Hash for CustomShaderImport {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.module_name().hash(state);
+    }
+}
+
+impl fmt::Display for CustomShaderImport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.module_name())
+    }
+}
+
+impl From<String> for CustomShaderImport {
+    fn from(name: String) -> Self {
+        CustomShaderImport::Name(name)
+    }
+}
+
+impl From<&str> for CustomShaderImport {
+    fn from(name: &str) -> Self {
+        CustomShaderImport::Name(name.to_owned())
+    }
+}
+
+impl From<Vec<String>> for CustomShaderImport {
+    fn from(components: Vec<String>) -> Self {
+        CustomShaderImport::ModulePath(components)
+    }
+}
+
 /// A reference to a shader asset.
 #[derive(Default)]
 pub enum ShaderRef {
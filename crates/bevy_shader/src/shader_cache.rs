@@ -1,11 +1,19 @@
 use crate::shader::*;
-use alloc::sync::Arc;
+use alloc::{borrow::Cow, sync::Arc};
 use bevy_asset::AssetId;
-use bevy_platform::collections::{hash_map::EntryRef, HashMap, HashSet};
-use core::hash::Hash;
+use bevy_platform::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    hash::FixedHasher,
+    sync::{Mutex, PoisonError},
+};
+use core::{
+    fmt,
+    hash::{BuildHasher, Hash, Hasher},
+    mem,
+};
 use naga::valid::Capabilities;
 use thiserror::Error;
-use tracing::debug;
+use tracing::{debug, warn};
 use wgpu_types::{DownlevelFlags, Features};
 
 /// Source of a shader module.
@@ -39,11 +47,37 @@ pub enum ShaderCacheSource<'a> {
 
 pub type CachedPipelineId = usize;
 
+/// The upper half of the UUIDs of shaders added with [`ShaderCache::add_inline_shader`].
+const INLINE_SHADER_UUID_PREFIX: u64 = 0x8f1d_2c4b_6a3e_5d70;
+
+/// Returns the id of the shader added with [`ShaderCache::add_inline_shader`] under `key`.
+pub fn inline_shader_id(key: &str) -> AssetId<Shader> {
+    AssetId::Uuid {
+        uuid: bevy_asset::uuid::Uuid::from_u64_pair(
+            INLINE_SHADER_UUID_PREFIX,
+            FixedHasher.hash_one(key),
+        ),
+    }
+}
+
+/// Identifies a processed shader module by the shader defs it was processed with and the entry
+/// point it is used with.
+type ProcessedShaderKey = (Box<[ShaderDefVal]>, Option<Box<str>>);
+
 struct ShaderData<ShaderModule> {
-    pipelines: HashSet<CachedPipelineId>,
-    processed_shaders: HashMap<Box<[ShaderDefVal]>, Arc<ShaderModule>>,
+    /// Behind a [`Mutex`] so that [`ShaderCache::get_processed`] can register pipelines with only
+    /// shared access to the cache.
+    pipelines: Mutex<HashSet<CachedPipelineId>>,
+    processed_shaders: HashMap<ProcessedShaderKey, Arc<ShaderModule>>,
+    /// The workgroup sizes of the compute entry points of the processed shaders, if known.
+    workgroup_sizes: HashMap<ProcessedShaderKey, [u32; 3]>,
+    naga_modules: HashMap<Box<[ShaderDefVal]>, Arc<naga::Module>>,
     resolved_imports: HashMap<ShaderImport, AssetId<Shader>>,
     dependents: HashSet<AssetId<Shader>>,
+    import_resolution: ImportResolutionState,
+    /// Hash of the source, shader defs, import path, validation setting and label of the
+    /// shader, used to skip reprocessing when an unchanged shader is set again.
+    source_hash: u64,
 }
 
 impl<T> Default for ShaderData<T> {
@@ -51,32 +85,160 @@ impl<T> Default for ShaderData<T> {
         Self {
             pipelines: Default::default(),
             processed_shaders: Default::default(),
+            workgroup_sizes: Default::default(),
+            naga_modules: Default::default(),
             resolved_imports: Default::default(),
             dependents: Default::default(),
+            import_resolution: Default::default(),
+            source_hash: 0,
         }
     }
 }
 
+/// Whether the imports of a shader, including the imports of its imports, are all available, so
+/// that it can be composed.
+#[derive(Default)]
+enum ImportResolutionState {
+    /// The imports weren't checked since the shader or one of its imports last changed.
+    #[default]
+    Unresolved,
+    /// All imports are available.
+    Resolved,
+    /// The shader can't be composed until this import is available.
+    Pending(ShaderImport),
+}
+
 pub struct ShaderCache<ShaderModule, RenderDevice> {
     data: HashMap<AssetId<Shader>, ShaderData<ShaderModule>>,
     load_module: fn(
         &RenderDevice,
         ShaderCacheSource,
         &ValidateShader,
-    ) -> Result<ShaderModule, PipelineCacheError>,
+        Option<&str>,
+    ) -> Result<ShaderModule, String>,
     #[cfg(feature = "shader_format_wesl")]
     asset_paths: HashMap<wesl::syntax::ModulePath, AssetId<Shader>>,
     shaders: HashMap<AssetId<Shader>, Shader>,
     import_path_shaders: HashMap<ShaderImport, AssetId<Shader>>,
+    /// The asset paths of the shaders that were loaded from one, kept after the shader is
+    /// removed, so [`PipelineCacheError::ShaderNotLoaded`] can name the missing shader.
+    shader_asset_paths: HashMap<AssetId<Shader>, String>,
     waiting_on_import: HashMap<ShaderImport, Vec<AssetId<Shader>>>,
     pub composer: naga_oil::compose::Composer,
+    /// If `true`, the composed [`naga::Module`] of each processed shader is kept around so it can
+    /// be retrieved with [`ShaderCache::get_naga_module`]. Disabled by default, as this roughly
+    /// doubles the memory used by processed shaders.
+    pub retain_naga_modules: bool,
+    /// How to resolve a shader def that is set to different values by different sources.
+    pub shader_def_conflict_policy: ShaderDefConflictPolicy,
+}
+
+/// Policy used by a [`ShaderCache`] when a shader def is given different values, for example
+/// by the pipeline descriptor and the global shader defs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ShaderDefConflictPolicy {
+    /// Keep the first value, in the order pipeline defs, then shader defs.
+    UseFirst,
+    /// Keep the last value. This matches the behavior of `naga_oil`.
+    UseLast,
+    /// Panic, reporting both values.
+    ///
+    /// Shaders are usually processed while compiling a pipeline on a background task, so this
+    /// panics that task rather than the system that queued the pipeline.
+    Panic,
+    /// Log a warning and keep the last value. This is the default.
+    #[default]
+    WarnUseLast,
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq, Debug, Hash)]
+impl ShaderDefConflictPolicy {
+    fn merge(
+        self,
+        shader_defs: &mut HashMap<String, ShaderDefVal>,
+        def: ShaderDefVal,
+        shader: AssetId<Shader>,
+    ) {
+        let key = def.name().to_string();
+        let Some(existing) = shader_defs.get(&key) else {
+            shader_defs.insert(key, def);
+            return;
+        };
+        if *existing == def {
+            return;
+        }
+
+        match self {
+            ShaderDefConflictPolicy::UseFirst => {}
+            ShaderDefConflictPolicy::UseLast => {
+                shader_defs.insert(key, def);
+            }
+            ShaderDefConflictPolicy::Panic => panic!(
+                "Conflicting values for shader def `{key}` in shader {shader}: {existing:?} and {def:?}"
+            ),
+            ShaderDefConflictPolicy::WarnUseLast => {
+                warn!(
+                    "Conflicting values for shader def `{key}` in shader {shader}: {existing:?} and {def:?}, using the latter"
+                );
+                shader_defs.insert(key, def);
+            }
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub enum ShaderDefVal {
     Bool(String, bool),
     Int(String, i32),
     UInt(String, u32),
+    /// A floating-point define.
+    ///
+    /// `naga_oil` has no support for float defines, so occurrences of `#{NAME}` in the source of
+    /// the processed shader are substituted with the value directly, and `#ifdef NAME` checks pass.
+    /// Imported modules are shared between all the shader defs they are processed with, so the
+    /// value can't be substituted there: processing a shader fails with a
+    /// [`PipelineCacheError::ProcessShaderError`] if one of its imports uses `#{NAME}`.
+    Float(String, f32),
+}
+
+// Floats are compared bitwise, so that `Eq` and `Hash` are consistent.
+impl PartialEq for ShaderDefVal {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ShaderDefVal::Bool(a, x), ShaderDefVal::Bool(b, y)) => a == b && x == y,
+            (ShaderDefVal::Int(a, x), ShaderDefVal::Int(b, y)) => a == b && x == y,
+            (ShaderDefVal::UInt(a, x), ShaderDefVal::UInt(b, y)) => a == b && x == y,
+            (ShaderDefVal::Float(a, x), ShaderDefVal::Float(b, y)) => {
+                a == b && x.to_bits() == y.to_bits()
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ShaderDefVal {}
+
+impl Hash for ShaderDefVal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match self {
+            ShaderDefVal::Bool(key, value) => (key, value).hash(state),
+            ShaderDefVal::Int(key, value) => (key, value).hash(state),
+            ShaderDefVal::UInt(key, value) => (key, value).hash(state),
+            ShaderDefVal::Float(key, value) => (key, value.to_bits()).hash(state),
+        }
+    }
+}
+
+impl ShaderDefVal {
+    /// The name of the shader def.
+    pub fn name(&self) -> &str {
+        match self {
+            ShaderDefVal::Bool(key, _)
+            | ShaderDefVal::Int(key, _)
+            | ShaderDefVal::UInt(key, _)
+            | ShaderDefVal::Float(key, _) => key,
+        }
+    }
 }
 
 impl From<&str> for ShaderDefVal {
@@ -97,11 +259,83 @@ impl ShaderDefVal {
             ShaderDefVal::Bool(_, def) => def.to_string(),
             ShaderDefVal::Int(_, def) => def.to_string(),
             ShaderDefVal::UInt(_, def) => def.to_string(),
+            // `Debug` round-trips and always yields a float literal, e.g. `1.0` rather than `1`.
+            ShaderDefVal::Float(_, def) => format!("{def:?}"),
         }
     }
 }
 
+/// Substitutes occurrences of `#{NAME}` for float shader defs, which `naga_oil` doesn't support.
+fn substitute_float_shader_defs<'a>(
+    source: &'a str,
+    shader_defs: &HashMap<String, ShaderDefVal>,
+) -> Cow<'a, str> {
+    let mut source = Cow::Borrowed(source);
+    for def in shader_defs.values() {
+        if let ShaderDefVal::Float(key, _) = def {
+            let pattern = format!("#{{{key}}}");
+            if source.contains(&pattern) {
+                source = Cow::Owned(source.replace(&pattern, &def.value_as_string()));
+            }
+        }
+    }
+    source
+}
+
+/// Returns an error if a float shader def is used in a module imported by `shader`, directly or
+/// through other imports, since they are only substituted in the source of `shader` itself.
+fn check_float_shader_defs_in_imports(
+    import_path_shaders: &HashMap<ShaderImport, AssetId<Shader>>,
+    shaders: &HashMap<AssetId<Shader>, Shader>,
+    shader: &Shader,
+    shader_defs: &HashMap<String, ShaderDefVal>,
+) -> Result<(), PipelineCacheError> {
+    let patterns = shader_defs
+        .values()
+        .filter_map(|def| match def {
+            ShaderDefVal::Float(key, _) => Some((key, format!("#{{{key}}}"))),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if patterns.is_empty() {
+        return Ok(());
+    }
+
+    let mut visited = HashSet::new();
+    let mut imports = shader.imports().collect::<Vec<_>>();
+    while let Some(import) = imports.pop() {
+        if !visited.insert(import) {
+            continue;
+        }
+        let Some(imported) = import_path_shaders
+            .get(import)
+            .and_then(|id| shaders.get(id))
+        else {
+            continue;
+        };
+        imports.extend(imported.imports());
+        if matches!(imported.source, Source::SpirV(_)) {
+            continue;
+        }
+        if let Some((key, _)) = patterns
+            .iter()
+            .find(|(_, pattern)| imported.source.as_str().contains(pattern.as_str()))
+        {
+            return Err(PipelineCacheError::ProcessShaderError(format!(
+                "float shader def `{key}` is used in the imported module `{}`, but float shader \
+                defs are only substituted in the shader being processed",
+                import.module_name()
+            )));
+        }
+    }
+    Ok(())
+}
+
 impl<ShaderModule, RenderDevice> ShaderCache<ShaderModule, RenderDevice> {
+    /// Creates a new shader cache.
+    ///
+    /// `load_module` creates the shader module from a processed shader, returning a description
+    /// of the error if the module is invalid.
     pub fn new(
         features: Features,
         downlevel: DownlevelFlags,
@@ -109,7 +343,8 @@ impl<ShaderModule, RenderDevice> ShaderCache<ShaderModule, RenderDevice> {
             &RenderDevice,
             ShaderCacheSource,
             &ValidateShader,
-        ) -> Result<ShaderModule, PipelineCacheError>,
+            Option<&str>,
+        ) -> Result<ShaderModule, String>,
     ) -> Self {
         let capabilities = get_capabilities(features, downlevel);
         #[cfg(debug_assertions)]
@@ -127,25 +362,203 @@ impl<ShaderModule, RenderDevice> ShaderCache<ShaderModule, RenderDevice> {
             asset_paths: Default::default(),
             shaders: Default::default(),
             import_path_shaders: Default::default(),
+            shader_asset_paths: Default::default(),
             waiting_on_import: Default::default(),
+            retain_naga_modules: false,
+            shader_def_conflict_policy: Default::default(),
         }
     }
 
-    #[expect(
-        clippy::result_large_err,
-        reason = "See https://github.com/bevyengine/bevy/issues/19220"
-    )]
+    /// Returns the composed [`naga::Module`] of a processed shader, if
+    /// [`retain_naga_modules`](Self::retain_naga_modules) was enabled when it was processed.
+    pub fn get_naga_module(
+        &self,
+        id: AssetId<Shader>,
+        shader_defs: &[ShaderDefVal],
+    ) -> Option<Arc<naga::Module>> {
+        let shader_defs = Self::normalize_shader_defs(shader_defs);
+        self.data
+            .get(&id)?
+            .naga_modules
+            .get(shader_defs.as_slice())
+            .cloned()
+    }
+
+    /// Returns the import path of the shader `id`, the reverse of the lookup used to resolve
+    /// imports, or `None` if the shader isn't loaded.
+    pub fn get_import_path_for_shader(&self, id: AssetId<Shader>) -> Option<&ShaderImport> {
+        self.shaders.get(&id).map(Shader::import_path)
+    }
+
+    /// The number of shader modules currently cached, across all shaders and their combinations
+    /// of shader defs and entry points.
+    pub fn shader_module_count(&self) -> usize {
+        self.data
+            .values()
+            .map(|data| data.processed_shaders.len())
+            .sum()
+    }
+
+    /// The number of distinct shaders known to the cache.
+    pub fn unique_shader_count(&self) -> usize {
+        self.shaders.len()
+    }
+
+    /// Returns the pipelines that use the shader `id`, i.e. that will be re-queued if it changes.
+    pub fn pipelines_using_shader(&self, id: AssetId<Shader>) -> Vec<CachedPipelineId> {
+        self.data
+            .get(&id)
+            .map(|data| {
+                data.pipelines
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .iter()
+                    .copied()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns all shaders that import `id`, directly or through other imports, i.e. the shaders
+    /// that will be reprocessed if it changes.
+    pub fn get_shader_dependents(&self, id: AssetId<Shader>) -> Vec<AssetId<Shader>> {
+        let mut dependents = Vec::new();
+        let mut visited = HashSet::new();
+        visited.insert(id);
+        let mut to_visit = vec![id];
+        while let Some(id) = to_visit.pop() {
+            let Some(data) = self.data.get(&id) else {
+                continue;
+            };
+            for &dependent in &data.dependents {
+                if visited.insert(dependent) {
+                    dependents.push(dependent);
+                    to_visit.push(dependent);
+                }
+            }
+        }
+        dependents
+    }
+
+    /// Returns the shaders directly imported by `id` that were resolved so far.
+    pub fn get_shader_imports(&self, id: AssetId<Shader>) -> Vec<AssetId<Shader>> {
+        self.data
+            .get(&id)
+            .map(|data| data.resolved_imports.values().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the shader module of `id` if it was already processed with `shader_defs` and
+    /// `entry_point`, registering `pipeline` as a user of the shader.
+    ///
+    /// Unlike [`ShaderCache::get`], this only needs shared access, so concurrent pipeline
+    /// compilations can use it without contending on a write lock, including for pipelines that
+    /// didn't use the shader before. It returns `None` if the shader still needs to be processed,
+    /// in which case [`ShaderCache::get`] must be called instead.
+    pub fn get_processed(
+        &self,
+        pipeline: CachedPipelineId,
+        id: AssetId<Shader>,
+        shader_defs: &[ShaderDefVal],
+        entry_point: Option<&str>,
+    ) -> Option<Arc<ShaderModule>> {
+        let data = self.data.get(&id)?;
+        let shader_defs = Self::normalize_shader_defs(shader_defs);
+        let module = data
+            .processed_shaders
+            .get(&(shader_defs.into(), entry_point.map(Into::into)))?;
+        data.pipelines
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(pipeline);
+        Some(module.clone())
+    }
+
+    /// Checks that all imports of `shader` are available before composing it, so that a shader
+    /// waiting on an import is only looked at again once that specific import was added.
+    fn resolve_imports(
+        state: &mut ImportResolutionState,
+        import_path_shaders: &HashMap<ShaderImport, AssetId<Shader>>,
+        shaders: &HashMap<AssetId<Shader>, Shader>,
+        shader: &Shader,
+    ) -> Result<(), PipelineCacheError> {
+        match state {
+            ImportResolutionState::Resolved => return Ok(()),
+            ImportResolutionState::Pending(import) if !import_path_shaders.contains_key(import) => {
+                return Err(PipelineCacheError::ShaderImportNotYetAvailable);
+            }
+            _ => {}
+        }
+
+        let mut visited = HashSet::new();
+        let mut imports = shader.imports().collect::<Vec<_>>();
+        while let Some(import) = imports.pop() {
+            if !visited.insert(import) {
+                continue;
+            }
+            match import_path_shaders
+                .get(import)
+                .and_then(|id| shaders.get(id))
+            {
+                Some(imported) => imports.extend(imported.imports()),
+                None => {
+                    *state = ImportResolutionState::Pending(import.clone());
+                    return Err(PipelineCacheError::ShaderImportNotYetAvailable);
+                }
+            }
+        }
+        *state = ImportResolutionState::Resolved;
+        Ok(())
+    }
+
+    /// Returns a [`PipelineCacheError::ShaderNotLoaded`] for `id`, with its asset path if it was
+    /// loaded from one before.
+    fn shader_not_loaded(&self, id: AssetId<Shader>) -> PipelineCacheError {
+        PipelineCacheError::ShaderNotLoaded(
+            id,
+            self.shader_asset_paths
+                .get(&id)
+                .map(|path| ShaderImport::AssetPath(path.clone())),
+        )
+    }
+
+    /// Returns the workgroup size of the compute entry point of a shader processed with the given
+    /// shader defs and entry point, if it was processed already.
+    ///
+    /// The workgroup size is only known for shaders composed by `naga_oil`, i.e. WGSL and GLSL
+    /// shaders.
+    pub fn get_workgroup_size(
+        &self,
+        id: AssetId<Shader>,
+        shader_defs: &[ShaderDefVal],
+        entry_point: Option<&str>,
+    ) -> Option<[u32; 3]> {
+        let data = self.data.get(&id)?;
+        let shader_defs = Self::normalize_shader_defs(shader_defs);
+        data.workgroup_sizes
+            .get(&(shader_defs.into(), entry_point.map(Into::into)))
+            .copied()
+    }
+
     fn add_import_to_composer(
         composer: &mut naga_oil::compose::Composer,
         import_path_shaders: &HashMap<ShaderImport, AssetId<Shader>>,
         shaders: &HashMap<AssetId<Shader>, Shader>,
         import: &ShaderImport,
+        import_chain: &mut Vec<ShaderImport>,
     ) -> Result<(), PipelineCacheError> {
         // Early out if we've already imported this module
         if composer.contains_module(&import.module_name()) {
             return Ok(());
         }
 
+        // Bail out if this module is already being imported further up the chain
+        if let Some(start) = import_chain.iter().position(|chained| chained == import) {
+            let mut cycle = import_chain[start..].to_vec();
+            cycle.push(import.clone());
+            return Err(PipelineCacheError::ShaderCycleDetected(cycle));
+        }
+
         // Check if the import is available (this handles the recursive import case)
         let shader = import_path_shaders
             .get(import)
@@ -153,55 +566,246 @@ impl<ShaderModule, RenderDevice> ShaderCache<ShaderModule, RenderDevice> {
             .ok_or(PipelineCacheError::ShaderImportNotYetAvailable)?;
 
         // Recurse down to ensure all import dependencies are met
-        for import in &shader.imports {
-            Self::add_import_to_composer(composer, import_path_shaders, shaders, import)?;
+        import_chain.push(import.clone());
+        for dependency in &shader.imports {
+            Self::add_import_to_composer(
+                composer,
+                import_path_shaders,
+                shaders,
+                dependency,
+                import_chain,
+            )?;
         }
+        import_chain.pop();
 
-        composer.add_composable_module(shader.into())?;
+        composer
+            .add_composable_module(shader.into())
+            .map_err(|err| PipelineCacheError::ProcessShaderError(err.emit_to_string(composer)))?;
         // if we fail to add a module the composer will tell us what is missing
 
         Ok(())
     }
 
-    #[expect(
-        clippy::result_large_err,
-        reason = "See https://github.com/bevyengine/bevy/issues/19220"
-    )]
+    /// Composes a shader with the given shader defs and returns the resulting module as WGSL.
+    ///
+    /// This runs the same composition as [`ShaderCache::get`] without creating a shader module,
+    /// which makes it useful to inspect the final source of a shader that fails validation or
+    /// misbehaves. Only shaders composed by `naga_oil`, i.e. WGSL and GLSL shaders, can be
+    /// exported.
+    pub fn export_naga_module(
+        &mut self,
+        id: AssetId<Shader>,
+        shader_defs: &[ShaderDefVal],
+    ) -> Result<String, PipelineCacheError> {
+        let shader = self
+            .shaders
+            .get(&id)
+            .ok_or_else(|| self.shader_not_loaded(id))?;
+        if matches!(shader.source, Source::SpirV(_) | Source::Wesl(_)) {
+            return Err(PipelineCacheError::CreateShaderModule(
+                ShaderModuleError::new(
+                    id,
+                    shader,
+                    "the shader is not composed by naga_oil and can't be exported".to_string(),
+                ),
+            ));
+        }
+
+        let label = shader_module_label(shader);
+        let module_error = |description: String| {
+            PipelineCacheError::CreateShaderModule(ShaderModuleError {
+                description,
+                label: label.clone(),
+                shader_id: id,
+            })
+        };
+
+        let naga = Self::compose_naga_module(
+            &mut self.composer,
+            &self.import_path_shaders,
+            &self.shaders,
+            self.shader_def_conflict_policy,
+            id,
+            shader_defs,
+        )?;
+        let module_info = naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            self.composer.capabilities,
+        )
+        .validate(&naga)
+        .map_err(|err| module_error(err.as_inner().to_string()))?;
+        naga::back::wgsl::write_string(
+            &naga,
+            &module_info,
+            naga::back::wgsl::WriterFlags::EXPLICIT_TYPES,
+        )
+        .map_err(|err| module_error(err.to_string()))
+    }
+
+    /// Composes a shader and its imports into a single [`naga::Module`] using `naga_oil`.
+    fn compose_naga_module(
+        composer: &mut naga_oil::compose::Composer,
+        import_path_shaders: &HashMap<ShaderImport, AssetId<Shader>>,
+        shaders: &HashMap<AssetId<Shader>, Shader>,
+        shader_def_conflict_policy: ShaderDefConflictPolicy,
+        id: AssetId<Shader>,
+        shader_defs: &[ShaderDefVal],
+    ) -> Result<naga::Module, PipelineCacheError> {
+        let shader = shaders
+            .get(&id)
+            .ok_or(PipelineCacheError::ShaderNotLoaded(id, None))?;
+
+        for import in shader.imports() {
+            Self::add_import_to_composer(
+                composer,
+                import_path_shaders,
+                shaders,
+                import,
+                &mut Vec::new(),
+            )?;
+        }
+
+        let mut merged_shader_defs = HashMap::default();
+        for def in shader_defs.iter().chain(shader.shader_defs.iter()) {
+            shader_def_conflict_policy.merge(&mut merged_shader_defs, def.clone(), id);
+        }
+
+        check_float_shader_defs_in_imports(
+            import_path_shaders,
+            shaders,
+            shader,
+            &merged_shader_defs,
+        )?;
+        let source = substitute_float_shader_defs(shader.source.as_str(), &merged_shader_defs);
+
+        let composer_shader_defs = merged_shader_defs
+            .into_values()
+            .map(|def| match def {
+                ShaderDefVal::Bool(k, v) => (k, naga_oil::compose::ShaderDefValue::Bool(v)),
+                ShaderDefVal::Int(k, v) => (k, naga_oil::compose::ShaderDefValue::Int(v)),
+                ShaderDefVal::UInt(k, v) => (k, naga_oil::compose::ShaderDefValue::UInt(v)),
+                ShaderDefVal::Float(k, _) => (k, naga_oil::compose::ShaderDefValue::Bool(true)),
+            })
+            .collect::<std::collections::HashMap<_, _>>();
+
+        composer
+            .make_naga_module(naga_oil::compose::NagaModuleDescriptor {
+                source: &source,
+                shader_defs: composer_shader_defs,
+                ..shader.into()
+            })
+            .map_err(|err| PipelineCacheError::ProcessShaderError(err.emit_to_string(composer)))
+    }
+
+    /// Sorts `shader_defs` by name and removes exact duplicates.
+    ///
+    /// Processed shaders are cached by their shader defs, so this makes sure the same set of
+    /// shader defs maps to the same cache entry, regardless of the order it was given in. This is
+    /// done on every lookup, so conflicting values are kept in their relative order and only
+    /// resolved with the [`shader_def_conflict_policy`](Self::shader_def_conflict_policy) when
+    /// the shader is processed.
+    fn normalize_shader_defs(shader_defs: &[ShaderDefVal]) -> Vec<ShaderDefVal> {
+        let mut shader_defs = shader_defs.to_vec();
+        shader_defs.sort_by(|a, b| a.name().cmp(b.name()));
+        shader_defs.dedup();
+        shader_defs
+    }
+
+    /// Composes the shader `id` with `shader_defs` and validates the result with naga, without
+    /// creating a shader module.
+    ///
+    /// This doesn't need a GPU, so it can be used in tests to check that a shader compiles with a
+    /// given set of shader defs. Only shaders composed by `naga_oil`, i.e. WGSL and GLSL shaders,
+    /// are validated; other shaders are accepted as-is. Nothing is cached.
+    pub fn validate(
+        &mut self,
+        id: AssetId<Shader>,
+        shader_defs: &[ShaderDefVal],
+    ) -> Result<(), PipelineCacheError> {
+        let shader_defs = Self::normalize_shader_defs(shader_defs);
+
+        let shader = self
+            .shaders
+            .get(&id)
+            .ok_or_else(|| self.shader_not_loaded(id))?;
+        if !matches!(shader.source, Source::Wgsl(_) | Source::Glsl(..)) {
+            return Ok(());
+        }
+
+        let data = self.data.entry(id).or_default();
+        Self::resolve_imports(
+            &mut data.import_resolution,
+            &self.import_path_shaders,
+            &self.shaders,
+            shader,
+        )?;
+
+        let naga = Self::compose_naga_module(
+            &mut self.composer,
+            &self.import_path_shaders,
+            &self.shaders,
+            self.shader_def_conflict_policy,
+            id,
+            &shader_defs,
+        )?;
+        naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            self.composer.capabilities,
+        )
+        .validate(&naga)
+        .map_err(|err| PipelineCacheError::NagaValidationFailed(Box::new(err.into_inner())))?;
+        Ok(())
+    }
+
+    /// Returns the shader module of `id` processed with `shader_defs`, processing it first if
+    /// necessary.
+    ///
+    /// The order of distinct `shader_defs` doesn't matter. If a shader def is given more than
+    /// once, the
+    /// [`shader_def_conflict_policy`](Self::shader_def_conflict_policy) decides which value is
+    /// used.
+    ///
+    /// Modules are cached per entry point, so a single shader asset can define several variants
+    /// of a stage (for example skinned and non-skinned vertex shaders) that are selected by the
+    /// entry point of the pipeline descriptor. Selecting a different entry point doesn't require
+    /// a separate shader asset.
     pub fn get(
         &mut self,
         render_device: &RenderDevice,
         pipeline: CachedPipelineId,
         id: AssetId<Shader>,
         shader_defs: &[ShaderDefVal],
+        entry_point: Option<&str>,
     ) -> Result<Arc<ShaderModule>, PipelineCacheError> {
+        let shader_defs = Self::normalize_shader_defs(shader_defs);
+        let shader_defs = shader_defs.as_slice();
+
         let shader = self
             .shaders
             .get(&id)
-            .ok_or(PipelineCacheError::ShaderNotLoaded(id))?;
+            .ok_or_else(|| self.shader_not_loaded(id))?;
 
         let data = self.data.entry(id).or_default();
-        let n_asset_imports = shader
-            .imports()
-            .filter(|import| matches!(import, ShaderImport::AssetPath(_)))
-            .count();
-        let n_resolved_asset_imports = data
-            .resolved_imports
-            .keys()
-            .filter(|import| matches!(import, ShaderImport::AssetPath(_)))
-            .count();
-        if n_asset_imports != n_resolved_asset_imports {
-            return Err(PipelineCacheError::ShaderImportNotYetAvailable);
-        }
+        Self::resolve_imports(
+            &mut data.import_resolution,
+            &self.import_path_shaders,
+            &self.shaders,
+            shader,
+        )?;
 
-        data.pipelines.insert(pipeline);
+        data.pipelines
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(pipeline);
 
         // PERF: this shader_defs clone isn't great. use raw_entry_mut when it stabilizes
-        let module = match data.processed_shaders.entry_ref(shader_defs) {
-            EntryRef::Occupied(entry) => entry.into_mut(),
-            EntryRef::Vacant(entry) => {
+        let key = (shader_defs.into(), entry_point.map(Into::into));
+        let module = match data.processed_shaders.entry(key) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
                 debug!(
-                    "processing shader {}, with shader defs {:?}",
-                    id, shader_defs
+                    "processing shader {}, with shader defs {:?} and entry point {:?}",
+                    id, shader_defs, entry_point
                 );
                 let shader_source = match &shader.source {
                     Source::SpirV(data) => ShaderCacheSource::SpirV(data.as_ref()),
@@ -243,37 +847,45 @@ impl<ShaderModule, RenderDevice> ShaderCache<ShaderModule, RenderDevice> {
                         }
                     }
                     _ => {
-                        for import in shader.imports() {
-                            Self::add_import_to_composer(
-                                &mut self.composer,
-                                &self.import_path_shaders,
-                                &self.shaders,
-                                import,
-                            )?;
+                        let naga = Self::compose_naga_module(
+                            &mut self.composer,
+                            &self.import_path_shaders,
+                            &self.shaders,
+                            self.shader_def_conflict_policy,
+                            id,
+                            shader_defs,
+                        )?;
+
+                        // Validate with naga first, which reports more precise errors than the
+                        // shader module creation.
+                        if matches!(shader.validate_shader, ValidateShader::Enabled) {
+                            naga::valid::Validator::new(
+                                naga::valid::ValidationFlags::all(),
+                                self.composer.capabilities,
+                            )
+                            .validate(&naga)
+                            .map_err(|err| {
+                                PipelineCacheError::NagaValidationFailed(Box::new(err.into_inner()))
+                            })?;
                         }
 
-                        let shader_defs = shader_defs
-                            .iter()
-                            .chain(shader.shader_defs.iter())
-                            .map(|def| match def.clone() {
-                                ShaderDefVal::Bool(k, v) => {
-                                    (k, naga_oil::compose::ShaderDefValue::Bool(v))
-                                }
-                                ShaderDefVal::Int(k, v) => {
-                                    (k, naga_oil::compose::ShaderDefValue::Int(v))
-                                }
-                                ShaderDefVal::UInt(k, v) => {
-                                    (k, naga_oil::compose::ShaderDefValue::UInt(v))
-                                }
-                            })
-                            .collect::<std::collections::HashMap<_, _>>();
-
-                        let naga = self.composer.make_naga_module(
-                            naga_oil::compose::NagaModuleDescriptor {
-                                shader_defs,
-                                ..shader.into()
-                            },
-                        )?;
+                        // With no entry point given, the shader must have a single compute entry
+                        // point for the workgroup size to be unambiguous.
+                        let mut compute_entry_points = naga.entry_points.iter().filter(|ep| {
+                            ep.stage == naga::ShaderStage::Compute
+                                && entry_point.is_none_or(|name| ep.name == name)
+                        });
+                        if let (Some(compute_entry_point), None) =
+                            (compute_entry_points.next(), compute_entry_points.next())
+                        {
+                            data.workgroup_sizes
+                                .insert(entry.key().clone(), compute_entry_point.workgroup_size);
+                        }
+
+                        if self.retain_naga_modules {
+                            data.naga_modules
+                                .insert(shader_defs.into(), Arc::new(naga.clone()));
+                        }
 
                         #[cfg(not(feature = "decoupled_naga"))]
                         {
@@ -298,8 +910,20 @@ impl<ShaderModule, RenderDevice> ShaderCache<ShaderModule, RenderDevice> {
                     }
                 };
 
-                let shader_module =
-                    (self.load_module)(render_device, shader_source, &shader.validate_shader)?;
+                let label = shader_module_label(shader);
+                let shader_module = (self.load_module)(
+                    render_device,
+                    shader_source,
+                    &shader.validate_shader,
+                    label.as_deref(),
+                )
+                .map_err(|description| {
+                    PipelineCacheError::CreateShaderModule(ShaderModuleError::new(
+                        id,
+                        shader,
+                        description,
+                    ))
+                })?;
 
                 entry.insert(Arc::new(shader_module))
             }
@@ -314,7 +938,16 @@ impl<ShaderModule, RenderDevice> ShaderCache<ShaderModule, RenderDevice> {
         while let Some(handle) = shaders_to_clear.pop() {
             if let Some(data) = self.data.get_mut(&handle) {
                 data.processed_shaders.clear();
-                pipelines_to_queue.extend(data.pipelines.iter().copied());
+                data.workgroup_sizes.clear();
+                data.naga_modules.clear();
+                data.import_resolution = ImportResolutionState::Unresolved;
+                pipelines_to_queue.extend(
+                    data.pipelines
+                        .get_mut()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .iter()
+                        .copied(),
+                );
                 shaders_to_clear.extend(data.dependents.iter().copied());
 
                 if let Some(Shader { import_path, .. }) = self.shaders.get(&handle) {
@@ -327,10 +960,46 @@ impl<ShaderModule, RenderDevice> ShaderCache<ShaderModule, RenderDevice> {
         pipelines_to_queue
     }
 
+    /// Adds a shader that isn't loaded through the asset system, so that other shaders can import
+    /// it with `#import key`.
+    ///
+    /// The id of the shader is [`inline_shader_id(&key)`](inline_shader_id), and any import path
+    /// set on `shader` is replaced by `key`. Adding a shader with the same key again replaces it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if another shader already uses `key` as its import path.
+    pub fn add_inline_shader(&mut self, key: String, shader: Shader) -> Vec<CachedPipelineId> {
+        let id = inline_shader_id(&key);
+        let import = ShaderImport::Custom(key.clone().into());
+        if let Some(&existing) = self.import_path_shaders.get(&import)
+            && existing != id
+        {
+            panic!("The shader import `{key}` is already used by shader {existing}");
+        }
+        self.set_shader(id, shader.with_import_path(key))
+    }
+
     pub fn set_shader(&mut self, id: AssetId<Shader>, shader: Shader) -> Vec<CachedPipelineId> {
+        let source_hash = hash_shader_source(&shader);
+        // Asset watchers can report a modification without the content changing, which would
+        // otherwise invalidate every processed module and re-queue all dependent pipelines.
+        if self.shaders.contains_key(&id)
+            && self
+                .data
+                .get(&id)
+                .is_some_and(|data| data.source_hash == source_hash)
+        {
+            return Vec::new();
+        }
+
         let pipelines_to_queue = self.clear(id);
+        self.data.entry(id).or_default().source_hash = source_hash;
         let path = shader.import_path();
         self.import_path_shaders.insert(path.clone(), id);
+        if let ShaderImport::AssetPath(asset_path) = path {
+            self.shader_asset_paths.insert(id, asset_path.clone());
+        }
         if let Some(waiting_shaders) = self.waiting_on_import.get_mut(path) {
             for waiting_shader in waiting_shaders.drain(..) {
                 // resolve waiting shader import
@@ -377,6 +1046,26 @@ impl<ShaderModule, RenderDevice> ShaderCache<ShaderModule, RenderDevice> {
     }
 }
 
+fn hash_shader_source(shader: &Shader) -> u64 {
+    let mut hasher = FixedHasher.build_hasher();
+    match &shader.source {
+        Source::Wgsl(source) | Source::Wesl(source) => source.hash(&mut hasher),
+        Source::Glsl(source, stage) => {
+            source.hash(&mut hasher);
+            stage.hash(&mut hasher);
+        }
+        Source::SpirV(data) => data.hash(&mut hasher),
+    }
+    mem::discriminant(&shader.source).hash(&mut hasher);
+    shader.shader_defs.hash(&mut hasher);
+    // These don't change the composed module, but are applied when it is processed or turned
+    // into a shader module.
+    shader.import_path.hash(&mut hasher);
+    mem::discriminant(&shader.validate_shader).hash(&mut hasher);
+    shader.label.hash(&mut hasher);
+    hasher.finish()
+}
+
 #[cfg(feature = "shader_format_wesl")]
 pub struct ShaderResolver<'a> {
     asset_paths: &'a HashMap<wesl::syntax::ModulePath, AssetId<Shader>>,
@@ -412,25 +1101,144 @@ impl<'a> wesl::Resolver for ShaderResolver<'a> {
 }
 
 /// Type of error returned by a `PipelineCache` when the creation of a GPU pipeline object failed.
-#[cfg_attr(
-    not(target_arch = "wasm32"),
-    expect(
-        clippy::large_enum_variant,
-        reason = "See https://github.com/bevyengine/bevy/issues/19220"
-    )
-)]
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Clone)]
 pub enum PipelineCacheError {
+    /// The shader isn't loaded yet. This contains the import path of the shader if it is known,
+    /// for example from the asset path of the handle the pipeline uses.
     #[error(
-        "Pipeline could not be compiled because the following shader could not be loaded: {0:?}"
+        "Pipeline could not be compiled because the following shader could not be loaded: {}",
+        format_shader(.0, .1.as_ref())
     )]
-    ShaderNotLoaded(AssetId<Shader>),
-    #[error(transparent)]
-    ProcessShaderError(#[from] naga_oil::compose::ComposerError),
+    ShaderNotLoaded(AssetId<Shader>, Option<ShaderImport>),
+    /// The shader could not be processed. This contains the formatted error, including the
+    /// location in the shader source where it occurred.
+    ///
+    /// Only the first [`MAX_DISPLAYED_ERROR_LINES`](PipelineCacheError::MAX_DISPLAYED_ERROR_LINES)
+    /// lines are displayed, which always include the first error location. The full message is
+    /// kept in this variant.
+    #[error("{}", truncate_lines(.0, PipelineCacheError::MAX_DISPLAYED_ERROR_LINES))]
+    ProcessShaderError(String),
     #[error("Shader import not yet available.")]
     ShaderImportNotYetAvailable,
     #[error("Could not create shader module: {0}")]
-    CreateShaderModule(String),
+    CreateShaderModule(ShaderModuleError),
+    /// The pipeline layout could not be created, for example because it uses more bind groups
+    /// than the device supports.
+    #[error("Pipeline layout could not be created: {0}")]
+    LayoutCreationFailed(String),
+    #[error("Shader import cycle detected: {}", format_import_chain(.0))]
+    ShaderCycleDetected(Vec<ShaderImport>),
+    /// The composed shader was rejected by `naga`'s validator. This is only checked for shaders
+    /// with [`ValidateShader::Enabled`].
+    #[error("Shader validation failed: {0}")]
+    NagaValidationFailed(Box<naga::valid::ValidationError>),
+}
+
+impl PipelineCacheError {
+    /// The maximum number of lines displayed for a [`PipelineCacheError::ProcessShaderError`].
+    pub const MAX_DISPLAYED_ERROR_LINES: usize = 20;
+
+    /// Returns a one-line summary of the error, for example to display it inline in an editor
+    /// next to the full error.
+    ///
+    /// This is the first line of the error message for errors carrying one, and a short
+    /// description of the error kind otherwise.
+    pub fn summary(&self) -> &str {
+        let message = match self {
+            PipelineCacheError::ShaderNotLoaded(..) => return "Shader not loaded",
+            PipelineCacheError::ProcessShaderError(message)
+            | PipelineCacheError::LayoutCreationFailed(message) => message.as_str(),
+            PipelineCacheError::ShaderImportNotYetAvailable => {
+                return "Shader import not yet available";
+            }
+            PipelineCacheError::CreateShaderModule(err) => err.description.as_str(),
+            PipelineCacheError::ShaderCycleDetected(_) => return "Shader import cycle detected",
+            PipelineCacheError::NagaValidationFailed(_) => return "Shader validation failed",
+        };
+        message
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or_default()
+    }
+}
+
+/// Keeps the first `max_lines` lines of `message`, noting how many lines were left out.
+fn truncate_lines(message: &str, max_lines: usize) -> Cow<'_, str> {
+    let line_count = message.lines().count();
+    if line_count <= max_lines {
+        return Cow::Borrowed(message);
+    }
+    let mut truncated = message
+        .lines()
+        .take(max_lines)
+        .collect::<Vec<_>>()
+        .join("\n");
+    truncated.push_str(&format!("\n... ({} more lines)", line_count - max_lines));
+    Cow::Owned(truncated)
+}
+
+/// A shader module could not be created, for example because it failed validation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaderModuleError {
+    /// The error reported while creating the module.
+    pub description: String,
+    /// The label of the shader module, if it has one, see [`Shader::label`].
+    pub label: Option<String>,
+    /// The shader the module was created from.
+    pub shader_id: AssetId<Shader>,
+}
+
+impl ShaderModuleError {
+    fn new(shader_id: AssetId<Shader>, shader: &Shader, description: String) -> Self {
+        Self {
+            description,
+            label: shader_module_label(shader),
+            shader_id,
+        }
+    }
+}
+
+impl core::error::Error for ShaderModuleError {}
+
+impl fmt::Display for ShaderModuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.label {
+            Some(label) => write!(f, "shader `{label}` ({}): ", self.shader_id)?,
+            None => write!(f, "shader {}: ", self.shader_id)?,
+        }
+        f.write_str(&self.description)
+    }
+}
+
+/// The label of the shader modules created from `shader`: its explicit label if set, and its path
+/// or import path otherwise.
+fn shader_module_label(shader: &Shader) -> Option<String> {
+    if let Some(label) = &shader.label {
+        return Some(label.to_string());
+    }
+    if !shader.path.is_empty() {
+        return Some(shader.path.clone());
+    }
+    match shader.import_path() {
+        ShaderImport::AssetPath(path) => (!path.is_empty()).then(|| path.clone()),
+        ShaderImport::Custom(custom) => Some(custom.module_name().into_owned()),
+    }
+}
+
+fn format_shader(id: &AssetId<Shader>, import_path: Option<&ShaderImport>) -> String {
+    match import_path {
+        Some(import_path) => import_path.module_name().into_owned(),
+        None => format!("{id:?}"),
+    }
+}
+
+fn format_import_chain(import_chain: &[ShaderImport]) -> String {
+    import_chain
+        .iter()
+        .map(|import| import.module_name().into_owned())
+        .collect::<Vec<_>>()
+        .join(" -> ")
 }
 
 // TODO: This needs to be kept up to date with the capabilities in the `create_validator` function in wgpu-core
@@ -549,3 +1357,394 @@ fn get_capabilities(features: Features, downlevel: DownlevelFlags) -> Capabiliti
 
     capabilities
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_shader_cache() -> ShaderCache<(), ()> {
+        ShaderCache::new(Features::empty(), DownlevelFlags::empty(), |_, _, _, _| {
+            Ok(())
+        })
+    }
+
+    fn test_id(n: u128) -> AssetId<Shader> {
+        AssetId::Uuid {
+            uuid: bevy_asset::uuid::Uuid::from_u128(n),
+        }
+    }
+
+    #[test]
+    fn float_shader_def_substitution() {
+        let source = "const SCALE: f32 = #{SCALE};\nconst OTHER: f32 = #{OTHER};";
+        let shader_defs = [
+            ShaderDefVal::Float("SCALE".to_string(), 1.5),
+            ShaderDefVal::Float("OTHER".to_string(), 2.0),
+            ShaderDefVal::UInt("UNUSED".to_string(), 3),
+        ]
+        .into_iter()
+        .map(|def| {
+            let key = match &def {
+                ShaderDefVal::Float(key, _) | ShaderDefVal::UInt(key, _) => key.clone(),
+                _ => unreachable!(),
+            };
+            (key, def)
+        })
+        .collect();
+
+        let source = substitute_float_shader_defs(source, &shader_defs);
+        assert_eq!(source, "const SCALE: f32 = 1.5;\nconst OTHER: f32 = 2.0;");
+        naga::front::wgsl::parse_str(&source).unwrap();
+    }
+
+    #[test]
+    fn float_shader_def_is_baked_into_the_processed_shader() {
+        let mut shader_cache = test_shader_cache();
+        let id = test_id(1);
+        shader_cache.set_shader(
+            id,
+            Shader::from_wgsl(
+                "const SCALE: f32 = #{SCALE};\n\
+                @compute @workgroup_size(1) fn main() { _ = SCALE; }",
+                "main.wgsl",
+            ),
+        );
+        let shader_defs = [ShaderDefVal::Float("SCALE".to_string(), 1.5)];
+
+        shader_cache.validate(id, &shader_defs).unwrap();
+        let source = shader_cache.export_naga_module(id, &shader_defs).unwrap();
+        assert!(source.contains("1.5f"), "{source}");
+    }
+
+    #[test]
+    fn float_shader_def_in_import_is_rejected() {
+        let mut shader_cache = test_shader_cache();
+        let shaders = [
+            "#define_import_path test::scale\nconst SCALE: f32 = #{SCALE};",
+            "#import test::scale\n@compute @workgroup_size(1) fn main() { _ = scale::SCALE; }",
+        ];
+        let ids = (1..=shaders.len() as u128).map(test_id).collect::<Vec<_>>();
+        for (index, (source, id)) in shaders.into_iter().zip(&ids).enumerate() {
+            shader_cache.set_shader(*id, Shader::from_wgsl(source, format!("{index}.wgsl")));
+        }
+
+        let shader_defs = [ShaderDefVal::Float("SCALE".to_string(), 1.5)];
+        assert!(matches!(
+            shader_cache.get(&(), 0, ids[1], &shader_defs, None),
+            Err(PipelineCacheError::ProcessShaderError(_))
+        ));
+    }
+
+    #[test]
+    fn import_cycle_is_detected() {
+        let mut shader_cache = test_shader_cache();
+        let shaders = [
+            "#define_import_path test::a\n#import test::b\nfn a() {}",
+            "#define_import_path test::b\n#import test::a\nfn b() {}",
+            "#import test::a\n@compute @workgroup_size(1) fn main() {}",
+        ];
+        let ids = (1..=shaders.len() as u128).map(test_id).collect::<Vec<_>>();
+        for (index, (source, id)) in shaders.into_iter().zip(&ids).enumerate() {
+            shader_cache.set_shader(*id, Shader::from_wgsl(source, format!("{index}.wgsl")));
+        }
+
+        let cycle = match shader_cache.get(&(), 0, ids[2], &[], None) {
+            Err(PipelineCacheError::ShaderCycleDetected(cycle)) => cycle,
+            other => panic!("expected an import cycle error, got {other:?}"),
+        };
+        assert_eq!(
+            cycle,
+            ["test::a", "test::b", "test::a"].map(|name| ShaderImport::Custom(name.into()))
+        );
+    }
+
+    #[test]
+    fn composition_waits_for_transitive_imports() {
+        let mut shader_cache = test_shader_cache();
+        let shaders = [
+            "#define_import_path test::a\n#import test::b\nfn a() {}",
+            "#define_import_path test::b\n#import test::c\nfn b() {}",
+            "#import test::a\n@compute @workgroup_size(1) fn main() {}",
+            "#define_import_path test::c\nfn c() {}",
+        ];
+        let ids = (1..=shaders.len() as u128).map(test_id).collect::<Vec<_>>();
+        for (index, (source, id)) in shaders.into_iter().zip(&ids).enumerate().take(3) {
+            shader_cache.set_shader(*id, Shader::from_wgsl(source, format!("{index}.wgsl")));
+        }
+
+        assert!(matches!(
+            shader_cache.get(&(), 0, ids[2], &[], None),
+            Err(PipelineCacheError::ShaderImportNotYetAvailable)
+        ));
+        assert!(matches!(
+            &shader_cache.data[&ids[2]].import_resolution,
+            ImportResolutionState::Pending(import) if *import == ShaderImport::Custom("test::c".into())
+        ));
+
+        shader_cache.set_shader(ids[3], Shader::from_wgsl(shaders[3], "3.wgsl"));
+        assert!(shader_cache.get(&(), 0, ids[2], &[], None).is_ok());
+    }
+
+    #[test]
+    fn compute_workgroup_size_is_reflected() {
+        let mut shader_cache = test_shader_cache();
+        let id = test_id(1);
+        let source =
+            "@compute @workgroup_size(8, 4, 1) fn a() {}\n@compute @workgroup_size(64) fn b() {}";
+        shader_cache.set_shader(id, Shader::from_wgsl(source, "main.wgsl"));
+        assert_eq!(shader_cache.get_workgroup_size(id, &[], Some("a")), None);
+
+        shader_cache.get(&(), 0, id, &[], Some("a")).unwrap();
+        shader_cache.get(&(), 0, id, &[], None).unwrap();
+        assert_eq!(
+            shader_cache.get_workgroup_size(id, &[], Some("a")),
+            Some([8, 4, 1])
+        );
+        assert_eq!(shader_cache.get_workgroup_size(id, &[], None), None);
+    }
+
+    #[test]
+    fn validate_reports_errors_without_device() {
+        let mut shader_cache =
+            ShaderCache::<(), ()>::new(Features::empty(), DownlevelFlags::empty(), |_, _, _, _| {
+                panic!("validation must not create shader modules")
+            });
+        let id = test_id(1);
+        let source = "@compute @workgroup_size(1) fn main() {\n#ifdef BROKEN\n    let x: u32 = 1.0;\n#endif\n}";
+        shader_cache.set_shader(id, Shader::from_wgsl(source, "main.wgsl"));
+
+        assert!(shader_cache.validate(id, &[]).is_ok());
+        assert!(shader_cache.validate(id, &["BROKEN".into()]).is_err());
+    }
+
+    #[test]
+    fn process_shader_error_is_truncated() {
+        let message = (1..=30)
+            .map(|line| format!("line {line}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let err = PipelineCacheError::ProcessShaderError(format!("\nerror: {message}"));
+        let displayed = err.to_string();
+        assert_eq!(
+            displayed.lines().count(),
+            PipelineCacheError::MAX_DISPLAYED_ERROR_LINES + 1
+        );
+        assert!(displayed.ends_with("... (11 more lines)"));
+        assert_eq!(err.summary(), "error: line 1");
+    }
+
+    #[test]
+    fn conflicting_shader_defs_use_last_value_by_default() {
+        let mut shader_defs = HashMap::default();
+        let policy = ShaderDefConflictPolicy::default();
+        policy.merge(
+            &mut shader_defs,
+            ShaderDefVal::UInt("N".to_string(), 1),
+            test_id(1),
+        );
+        policy.merge(
+            &mut shader_defs,
+            ShaderDefVal::UInt("N".to_string(), 2),
+            test_id(1),
+        );
+        assert_eq!(
+            shader_defs.get("N"),
+            Some(&ShaderDefVal::UInt("N".to_string(), 2))
+        );
+    }
+
+    #[test]
+    fn float_shader_def_eq_hash() {
+        let hasher = FixedHasher;
+        let a = ShaderDefVal::Float("A".to_string(), 0.1);
+        let b = ShaderDefVal::Float("A".to_string(), 0.1);
+        assert_eq!(a, b);
+        assert_eq!(hasher.hash_one(&a), hasher.hash_one(&b));
+        assert_ne!(a, ShaderDefVal::Float("A".to_string(), 0.2));
+    }
+
+    #[test]
+    fn unchanged_shader_is_not_reprocessed() {
+        let mut shader_cache = test_shader_cache();
+        let id = test_id(1);
+        let source = "@compute @workgroup_size(1) fn main() {}";
+        shader_cache.set_shader(id, Shader::from_wgsl(source, "main.wgsl"));
+        shader_cache.get(&(), 0, id, &[], None).unwrap();
+
+        let pipelines = shader_cache.set_shader(id, Shader::from_wgsl(source, "main.wgsl"));
+        assert!(pipelines.is_empty());
+
+        let pipelines = shader_cache.set_shader(
+            id,
+            Shader::from_wgsl("@compute @workgroup_size(2) fn main() {}", "main.wgsl"),
+        );
+        assert_eq!(pipelines, [0]);
+    }
+
+    #[test]
+    fn shader_with_changed_label_or_validation_is_reprocessed() {
+        let mut shader_cache = test_shader_cache();
+        let id = test_id(1);
+        let shader = Shader::from_wgsl("@compute @workgroup_size(1) fn main() {}", "main.wgsl");
+        shader_cache.set_shader(id, shader.clone());
+        shader_cache.get(&(), 0, id, &[], None).unwrap();
+
+        let labeled = Shader {
+            label: Some("labeled".into()),
+            ..shader.clone()
+        };
+        assert_eq!(shader_cache.set_shader(id, labeled.clone()), [0]);
+        shader_cache.get(&(), 0, id, &[], None).unwrap();
+
+        let validated = Shader {
+            validate_shader: ValidateShader::Enabled,
+            ..labeled
+        };
+        assert_eq!(shader_cache.set_shader(id, validated), [0]);
+    }
+
+    #[test]
+    fn inline_shader_is_importable() {
+        let mut shader_cache = test_shader_cache();
+        let id = test_id(1);
+        shader_cache.set_shader(
+            id,
+            Shader::from_wgsl(
+                "#import test::lib\n@compute @workgroup_size(1) fn main() {}",
+                "main.wgsl",
+            ),
+        );
+        shader_cache.add_inline_shader(
+            "test::lib".to_string(),
+            Shader::from_wgsl("fn f() -> f32 { return 1.0; }", "lib.wgsl"),
+        );
+
+        assert_eq!(
+            shader_cache.get_shader_imports(id),
+            [inline_shader_id("test::lib")]
+        );
+    }
+
+    #[test]
+    fn capabilities_match_device_features() {
+        assert_eq!(
+            get_capabilities(Features::empty(), DownlevelFlags::empty()),
+            Capabilities::empty()
+        );
+
+        let feature_capabilities = [
+            (Features::PUSH_CONSTANTS, Capabilities::PUSH_CONSTANT),
+            (Features::SHADER_F64, Capabilities::FLOAT64),
+            (Features::SHADER_F16, Capabilities::SHADER_FLOAT16),
+            (
+                Features::SHADER_PRIMITIVE_INDEX,
+                Capabilities::PRIMITIVE_INDEX,
+            ),
+            (
+                Features::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING,
+                Capabilities::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING
+                    | Capabilities::SAMPLER_NON_UNIFORM_INDEXING,
+            ),
+            (
+                Features::STORAGE_TEXTURE_ARRAY_NON_UNIFORM_INDEXING,
+                Capabilities::STORAGE_TEXTURE_ARRAY_NON_UNIFORM_INDEXING,
+            ),
+            (
+                Features::UNIFORM_BUFFER_BINDING_ARRAYS,
+                Capabilities::UNIFORM_BUFFER_ARRAY_NON_UNIFORM_INDEXING,
+            ),
+            (
+                Features::TEXTURE_FORMAT_16BIT_NORM,
+                Capabilities::STORAGE_TEXTURE_16BIT_NORM_FORMATS,
+            ),
+            (Features::MULTIVIEW, Capabilities::MULTIVIEW),
+            (
+                Features::SHADER_EARLY_DEPTH_TEST,
+                Capabilities::EARLY_DEPTH_TEST,
+            ),
+            (Features::SHADER_INT64, Capabilities::SHADER_INT64),
+            (
+                Features::SHADER_INT64_ATOMIC_MIN_MAX,
+                Capabilities::SHADER_INT64_ATOMIC_MIN_MAX,
+            ),
+            (
+                Features::SHADER_INT64_ATOMIC_ALL_OPS,
+                Capabilities::SHADER_INT64_ATOMIC_MIN_MAX
+                    | Capabilities::SHADER_INT64_ATOMIC_ALL_OPS,
+            ),
+            (Features::EXPERIMENTAL_RAY_QUERY, Capabilities::RAY_QUERY),
+            (
+                Features::DUAL_SOURCE_BLENDING,
+                Capabilities::DUAL_SOURCE_BLENDING,
+            ),
+            (Features::CLIP_DISTANCES, Capabilities::CLIP_DISTANCE),
+            (Features::SUBGROUP, Capabilities::SUBGROUP),
+            (Features::SUBGROUP_BARRIER, Capabilities::SUBGROUP_BARRIER),
+            (
+                Features::SUBGROUP_VERTEX,
+                Capabilities::SUBGROUP | Capabilities::SUBGROUP_VERTEX_STAGE,
+            ),
+            (
+                Features::SHADER_FLOAT32_ATOMIC,
+                Capabilities::SHADER_FLOAT32_ATOMIC,
+            ),
+            (Features::TEXTURE_ATOMIC, Capabilities::TEXTURE_ATOMIC),
+            (
+                Features::TEXTURE_INT64_ATOMIC,
+                Capabilities::TEXTURE_INT64_ATOMIC,
+            ),
+            (
+                Features::EXPERIMENTAL_RAY_HIT_VERTEX_RETURN,
+                Capabilities::RAY_HIT_VERTEX_POSITION,
+            ),
+        ];
+        let mut all_capabilities = Capabilities::empty();
+        for (feature, capabilities) in feature_capabilities {
+            assert_eq!(
+                get_capabilities(feature, DownlevelFlags::empty()),
+                capabilities,
+                "unexpected capabilities for {feature:?}"
+            );
+            all_capabilities |= capabilities;
+        }
+
+        let downlevel_capabilities = [
+            (
+                DownlevelFlags::MULTISAMPLED_SHADING,
+                Capabilities::MULTISAMPLED_SHADING,
+            ),
+            (
+                DownlevelFlags::CUBE_ARRAY_TEXTURES,
+                Capabilities::CUBE_ARRAY_TEXTURES,
+            ),
+        ];
+        for (downlevel, capabilities) in downlevel_capabilities {
+            assert_eq!(
+                get_capabilities(Features::empty(), downlevel),
+                capabilities,
+                "unexpected capabilities for {downlevel:?}"
+            );
+            all_capabilities |= capabilities;
+        }
+
+        // Every capability enabled by some feature is enabled when all features are.
+        assert!(get_capabilities(Features::all(), DownlevelFlags::all()).contains(all_capabilities));
+    }
+
+    #[test]
+    fn shader_def_order_does_not_affect_cache_key() {
+        let mut shader_cache = test_shader_cache();
+        let id = test_id(1);
+        let source = "@compute @workgroup_size(1) fn main() {}";
+        shader_cache.set_shader(id, Shader::from_wgsl(source, "main.wgsl"));
+
+        let a = ShaderDefVal::Bool("A".to_string(), true);
+        let b = ShaderDefVal::UInt("B".to_string(), 2);
+        shader_cache
+            .get(&(), 0, id, &[a.clone(), b.clone()], None)
+            .unwrap();
+        shader_cache.get(&(), 1, id, &[b, a], None).unwrap();
+
+        assert_eq!(shader_cache.data[&id].processed_shaders.len(), 1);
+    }
+}
@@ -155,7 +155,7 @@ fn allocate_blas(
         flags: AccelerationStructureGeometryFlags::OPAQUE,
     };
 
-    let blas = render_device.wgpu_device().create_blas(
+    let blas = render_device.create_blas(
         &CreateBlasDescriptor {
             label: Some(&asset_id.to_string()),
             flags: AccelerationStructureFlags::PREFER_FAST_TRACE
@@ -243,7 +243,7 @@ impl render_graph::Node for GameOfLifeNode {
                         self.state = GameOfLifeState::Init;
                     }
                     // If the shader hasn't loaded yet, just wait.
-                    CachedPipelineState::Err(PipelineCacheError::ShaderNotLoaded(_)) => {}
+                    CachedPipelineState::Err(PipelineCacheError::ShaderNotLoaded(..)) => {}
                     CachedPipelineState::Err(err) => {
                         panic!("Initializing assets/{SHADER_ASSET_PATH}:\n{err}")
                     }
@@ -251,8 +251,9 @@ impl render_graph::Node for GameOfLifeNode {
                 }
             }
             GameOfLifeState::Init => {
-                if let CachedPipelineState::Ok(_) =
-                    pipeline_cache.get_compute_pipeline_state(pipeline.update_pipeline)
+                if pipeline_cache
+                    .get_compute_pipeline_state(pipeline.update_pipeline)
+                    .is_ready()
                 {
                     self.state = GameOfLifeState::Update(1);
                 }
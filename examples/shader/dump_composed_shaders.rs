@@ -0,0 +1,139 @@
+//! Dumps the final WGSL of every shader used by a pipeline, after imports and shader defs were
+//! resolved. This is useful to debug shaders that fail validation or produce incorrect output.
+//!
+//! Run with the `DUMP_SHADERS_DIR` environment variable set to the directory the shaders should
+//! be written to, for example:
+//!
+//! ```sh
+//! DUMP_SHADERS_DIR=composed_shaders cargo run --example dump_composed_shaders
+//! ```
+
+use std::{fs, path::PathBuf};
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_resource::{PipelineCache, PipelineReady, ReadyPipelineId},
+        Render, RenderApp, RenderSystems,
+    },
+    shader::ShaderDefVal,
+};
+
+fn main() {
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins).add_systems(Startup, setup);
+
+    match std::env::var("DUMP_SHADERS_DIR") {
+        Ok(dir) => {
+            app.add_plugins(DumpComposedShadersPlugin { dir: dir.into() });
+        }
+        Err(_) => info!("Set DUMP_SHADERS_DIR to dump the composed shaders to a directory"),
+    }
+
+    app.run();
+}
+
+/// set up a simple 3D scene, so that a few pipelines are created
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::default())),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
+        Transform::from_xyz(0.0, 0.5, 0.0),
+    ));
+    commands.spawn((
+        PointLight {
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(4.0, 8.0, 4.0),
+    ));
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(-2.0, 2.5, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+}
+
+struct DumpComposedShadersPlugin {
+    dir: PathBuf,
+}
+
+/// The directory the composed shaders are written to, in the render world.
+#[derive(Resource)]
+struct DumpComposedShadersDir(PathBuf);
+
+impl Plugin for DumpComposedShadersPlugin {
+    fn build(&self, app: &mut App) {
+        fs::create_dir_all(&self.dir).expect("Failed to create the shader dump directory");
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .insert_resource(DumpComposedShadersDir(self.dir.clone()))
+            .add_systems(Render, dump_composed_shaders.in_set(RenderSystems::Cleanup));
+    }
+}
+
+/// Writes the composed shaders of each pipeline once it's ready.
+fn dump_composed_shaders(
+    mut pipeline_ready: MessageReader<PipelineReady>,
+    mut pipeline_cache: ResMut<PipelineCache>,
+    dir: Res<DumpComposedShadersDir>,
+) {
+    for PipelineReady(id) in pipeline_ready.read() {
+        // Collect the shader stages first, since exporting needs mutable access to the cache.
+        let (index, stages) = match *id {
+            ReadyPipelineId::Render(id) => {
+                let descriptor = pipeline_cache.get_render_pipeline_descriptor(id);
+                let mut stages = vec![(
+                    "vertex",
+                    descriptor.vertex.shader.id(),
+                    descriptor.vertex.shader_defs.clone(),
+                )];
+                if let Some(fragment) = &descriptor.fragment {
+                    stages.push((
+                        "fragment",
+                        fragment.shader.id(),
+                        fragment.shader_defs.clone(),
+                    ));
+                }
+                (id.id(), stages)
+            }
+            ReadyPipelineId::Compute(id) => {
+                let descriptor = pipeline_cache.get_compute_pipeline_descriptor(id);
+                let stages = vec![(
+                    "compute",
+                    descriptor.shader.id(),
+                    descriptor.shader_defs.clone(),
+                )];
+                (id.id(), stages)
+            }
+        };
+
+        for (stage, shader, shader_defs) in stages {
+            let path = dir.0.join(format!("pipeline_{index}_{stage}.wgsl"));
+            match pipeline_cache.export_naga_module(shader, &shader_defs) {
+                Ok(wgsl) => {
+                    let header = format_header(&shader_defs);
+                    if let Err(err) = fs::write(&path, header + &wgsl) {
+                        error!("Failed to write {}: {err}", path.display());
+                    }
+                }
+                Err(err) => warn!("Failed to export the {stage} shader of pipeline {index}: {err}"),
+            }
+        }
+    }
+}
+
+/// Lists the shader defs the shader was composed with as a comment.
+fn format_header(shader_defs: &[ShaderDefVal]) -> String {
+    let mut header = String::from("// Shader defs:\n");
+    for shader_def in shader_defs {
+        header += &format!("//   {shader_def:?}\n");
+    }
+    header + "\n"
+}